@@ -0,0 +1,255 @@
+//! Approximate comparison of responses, where confidences only need to be within an epsilon
+//! of each other. Used by the `approx_eq` and `approx_diff` methods on response types
+
+use crate::{
+    message::{MessageEntity, MessageIntent, MessageResponse, MessageTrait},
+    speech::{UnderstandingEntity, UnderstandingResponse, UnderstandingTrait},
+    Confidence,
+};
+use std::{collections::HashMap, fmt::Debug};
+
+/// Collects a human-readable description of every difference between two values, each
+/// prefixed with the path to the differing field (ex. `intents[0].confidence`)
+pub(crate) struct Differences {
+    epsilon: f64,
+    differences: Vec<String>,
+}
+
+impl Differences {
+    fn new(epsilon: f64) -> Self {
+        Self {
+            epsilon,
+            differences: Vec::new(),
+        }
+    }
+
+    fn exact<T: PartialEq + Debug>(&mut self, path: &str, left: &T, right: &T) {
+        if left != right {
+            self.differences
+                .push(format!("{path}: {left:?} != {right:?}"));
+        }
+    }
+
+    fn confidence(&mut self, path: &str, left: Confidence, right: Confidence) {
+        let (left, right) = (left.into_inner(), right.into_inner());
+
+        if (left - right).abs() > self.epsilon {
+            self.differences.push(format!(
+                "{path}: {left} != {right} (difference exceeds {})",
+                self.epsilon
+            ));
+        }
+    }
+
+    /// Compare lists element by element, since their order is meaningful (ex. intents are
+    /// sorted by confidence)
+    fn list<T>(
+        &mut self,
+        path: &str,
+        left: &[T],
+        right: &[T],
+        compare: impl Fn(&mut Self, &str, &T, &T),
+    ) {
+        if left.len() != right.len() {
+            self.differences
+                .push(format!("{path}: length {} != {}", left.len(), right.len()));
+            return;
+        }
+
+        for (index, (left, right)) in left.iter().zip(right).enumerate() {
+            compare(self, &format!("{path}[{index}]"), left, right);
+        }
+    }
+
+    /// Compare maps key by key, regardless of iteration order
+    fn map<T>(
+        &mut self,
+        path: &str,
+        left: &HashMap<String, T>,
+        right: &HashMap<String, T>,
+        compare: impl Fn(&mut Self, &str, &T, &T),
+    ) {
+        let mut keys: Vec<&String> = left.keys().chain(right.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            let key_path = format!("{path}[{key:?}]");
+
+            match (left.get(key), right.get(key)) {
+                (Some(left), Some(right)) => compare(self, &key_path, left, right),
+                (Some(_), None) => self.differences.push(format!("{key_path}: only on left")),
+                (None, Some(_)) => self.differences.push(format!("{key_path}: only on right")),
+                (None, None) => unreachable!("key was taken from one of the maps"),
+            }
+        }
+    }
+
+    fn intent(&mut self, path: &str, left: &MessageIntent, right: &MessageIntent) {
+        self.exact(&format!("{path}.id"), &left.id, &right.id);
+        self.exact(&format!("{path}.name"), &left.name, &right.name);
+        self.confidence(
+            &format!("{path}.confidence"),
+            left.confidence,
+            right.confidence,
+        );
+    }
+
+    fn message_entity(&mut self, path: &str, left: &MessageEntity, right: &MessageEntity) {
+        self.exact(&format!("{path}.id"), &left.id, &right.id);
+        self.exact(&format!("{path}.name"), &left.name, &right.name);
+        self.exact(&format!("{path}.role"), &left.role, &right.role);
+        self.exact(&format!("{path}.start"), &left.start, &right.start);
+        self.exact(&format!("{path}.end"), &left.end, &right.end);
+        self.exact(&format!("{path}.body"), &left.body, &right.body);
+        self.confidence(
+            &format!("{path}.confidence"),
+            left.confidence,
+            right.confidence,
+        );
+        self.map(
+            &format!("{path}.entities"),
+            &left.entities,
+            &right.entities,
+            |differences, path, left, right| {
+                differences.list(path, left, right, Self::message_entity)
+            },
+        );
+        self.exact(&format!("{path}.value"), &left.value, &right.value);
+        self.exact(&format!("{path}.unit"), &left.unit, &right.unit);
+        self.exact(&format!("{path}.grain"), &left.grain, &right.grain);
+        self.exact(
+            &format!("{path}.normalized"),
+            &left.normalized,
+            &right.normalized,
+        );
+        self.exact(&format!("{path}.from"), &left.from, &right.from);
+        self.exact(&format!("{path}.to"), &left.to, &right.to);
+        self.exact(&format!("{path}.values"), &left.values, &right.values);
+        self.exact(&format!("{path}.resolved"), &left.resolved, &right.resolved);
+    }
+
+    fn message_trait(&mut self, path: &str, left: &MessageTrait, right: &MessageTrait) {
+        self.exact(&format!("{path}.id"), &left.id, &right.id);
+        self.exact(&format!("{path}.value"), &left.value, &right.value);
+        self.confidence(
+            &format!("{path}.confidence"),
+            left.confidence,
+            right.confidence,
+        );
+    }
+
+    fn understanding_entity(
+        &mut self,
+        path: &str,
+        left: &UnderstandingEntity,
+        right: &UnderstandingEntity,
+    ) {
+        self.exact(&format!("{path}.id"), &left.id, &right.id);
+        self.exact(&format!("{path}.name"), &left.name, &right.name);
+        self.exact(&format!("{path}.role"), &left.role, &right.role);
+        self.exact(&format!("{path}.start"), &left.start, &right.start);
+        self.exact(&format!("{path}.end"), &left.end, &right.end);
+        self.exact(&format!("{path}.body"), &left.body, &right.body);
+        self.confidence(
+            &format!("{path}.confidence"),
+            left.confidence,
+            right.confidence,
+        );
+        self.exact(&format!("{path}.value"), &left.value, &right.value);
+        self.map(
+            &format!("{path}.entities"),
+            &left.entities,
+            &right.entities,
+            |differences, path, left, right| {
+                differences.list(path, left, right, Self::understanding_entity)
+            },
+        );
+    }
+
+    fn understanding_trait(
+        &mut self,
+        path: &str,
+        left: &UnderstandingTrait,
+        right: &UnderstandingTrait,
+    ) {
+        self.exact(&format!("{path}.id"), &left.id, &right.id);
+        self.exact(&format!("{path}.value"), &left.value, &right.value);
+        self.confidence(
+            &format!("{path}.confidence"),
+            left.confidence,
+            right.confidence,
+        );
+    }
+}
+
+/// Every difference between two message responses
+pub(crate) fn message_differences(
+    left: &MessageResponse,
+    right: &MessageResponse,
+    epsilon: f64,
+) -> Vec<String> {
+    let mut differences = Differences::new(epsilon);
+
+    differences.exact("text", &left.text, &right.text);
+    differences.list(
+        "intents",
+        &left.intents,
+        &right.intents,
+        Differences::intent,
+    );
+    differences.map(
+        "entities",
+        &left.entities,
+        &right.entities,
+        |differences, path, left, right| {
+            differences.list(path, left, right, Differences::message_entity)
+        },
+    );
+    differences.map(
+        "traits",
+        &left.traits,
+        &right.traits,
+        |differences, path, left, right| {
+            differences.list(path, left, right, Differences::message_trait)
+        },
+    );
+    differences.exact("debug", &left.debug, &right.debug);
+
+    differences.differences
+}
+
+/// Every difference between two understanding responses
+pub(crate) fn understanding_differences(
+    left: &UnderstandingResponse,
+    right: &UnderstandingResponse,
+    epsilon: f64,
+) -> Vec<String> {
+    let mut differences = Differences::new(epsilon);
+
+    differences.exact("text", &left.text, &right.text);
+    differences.list(
+        "intents",
+        &left.intents,
+        &right.intents,
+        Differences::intent,
+    );
+    differences.map(
+        "entities",
+        &left.entities,
+        &right.entities,
+        |differences, path, left, right| {
+            differences.list(path, left, right, Differences::understanding_entity)
+        },
+    );
+    differences.map(
+        "traits",
+        &left.traits,
+        &right.traits,
+        |differences, path, left, right| {
+            differences.list(path, left, right, Differences::understanding_trait)
+        },
+    );
+
+    differences.differences
+}
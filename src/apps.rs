@@ -0,0 +1,189 @@
+//! Interacting with wit apps
+
+use crate::{client::WitClient, errors::Error};
+use futures::Stream;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::Value;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+/// Information about a wit app
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct AppInfo {
+    /// The id of the app
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
+    pub id: String,
+    /// The name of the app
+    pub name: String,
+    /// The language of the app, as an ISO 639-1 code (ex. "en")
+    pub lang: String,
+}
+
+/// Whether an app is training, as reported by wit. Wit trains an app some time after its
+/// utterances, intents, entities, or traits change
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrainingStatus {
+    /// The app is trained on its current data
+    Done,
+    /// The app's data has changed, and training will start soon
+    Scheduled,
+    /// The app is training
+    Ongoing,
+    /// A status this crate does not know about
+    #[serde(other)]
+    Unknown,
+}
+
+/// The part of wit's response for a single app that describes its training
+#[derive(Deserialize)]
+struct TrainingInfo {
+    training_status: TrainingStatus,
+}
+
+impl WitClient {
+    /// Returns information about the app that this client's token belongs to, without
+    /// changing anything. This can be used to check that a token is valid before starting
+    /// a batch job, and to confirm which app the client is talking to.
+    ///
+    /// An invalid token results in `Error::AuthError`, and a token that wit lists no apps for
+    /// in `Error::NoAppForToken`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::apps::AppInfo;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let app: AppInfo = wit_client.whoami().await.unwrap();
+    ///
+    /// println!("using app {} ({})", app.name, app.id);
+    /// # })
+    /// ```
+    pub async fn whoami(&self) -> Result<AppInfo, Error> {
+        // a server access token only has access to its own app, so it is the only one listed
+        let url_params = vec![
+            (String::from("limit"), String::from("1")),
+            (String::from("offset"), String::from("0")),
+        ];
+
+        let apps: Vec<AppInfo> = self
+            .make_request(Method::GET, "/apps", url_params, Option::<Value>::None)
+            .await?;
+
+        apps.into_iter().next().ok_or(Error::NoAppForToken)
+    }
+
+    /// Returns the language of the app that this client's token belongs to, as an ISO 639-1
+    /// code (ex. "en"). This is the `lang` of `whoami`, for choosing a locale without
+    /// hardcoding it alongside the token
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let language: String = wit_client.get_app_language().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn get_app_language(&self) -> Result<String, Error> {
+        Ok(self.whoami().await?.lang)
+    }
+
+    /// Returns the training status of the app that this client's token belongs to
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::apps::TrainingStatus;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let status: TrainingStatus = wit_client.get_training_status().await.unwrap();
+    /// # })
+    /// ```
+    pub async fn get_training_status(&self) -> Result<TrainingStatus, Error> {
+        let app = self.whoami().await?;
+
+        self.get_app_training_status(&app.id).await
+    }
+
+    async fn get_app_training_status(&self, app_id: &str) -> Result<TrainingStatus, Error> {
+        let endpoint = format!("/apps/{app_id}");
+
+        let info: TrainingInfo = self
+            .make_request(Method::GET, &endpoint, vec![], Option::<Value>::None)
+            .await
+            .map_err(|err| err.map_not_found(Error::AppNotFound))?;
+
+        Ok(info.training_status)
+    }
+
+    /// Poll the training status of the app that this client's token belongs to, emitting it
+    /// once immediately and then every `poll_interval`, until training is `Done`. The stream
+    /// ends after emitting `Done` or an error, or as soon as `cancellation` is cancelled
+    /// (including during a request or while waiting for the next poll). Dropping the stream
+    /// also stops polling
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use futures::TryStreamExt;
+    /// # use std::time::Duration;
+    /// # use tokio_util::sync::CancellationToken;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let cancellation = CancellationToken::new();
+    ///
+    /// let mut statuses = Box::pin(
+    ///     wit_client.training_status_stream(Duration::from_secs(5), cancellation.clone()),
+    /// );
+    ///
+    /// while let Some(status) = statuses.try_next().await.unwrap() {
+    ///     println!("training: {status:?}");
+    /// }
+    /// # })
+    /// ```
+    pub fn training_status_stream(
+        &self,
+        poll_interval: Duration,
+        cancellation: CancellationToken,
+    ) -> impl Stream<Item = Result<TrainingStatus, Error>> + Send + '_ {
+        // the app id, once known, and whether the stream has finished
+        let state = (None::<String>, false);
+
+        futures::stream::unfold(state, move |(app_id, finished)| {
+            let cancellation = cancellation.clone();
+
+            async move {
+                if finished {
+                    return None;
+                }
+
+                let poll = async {
+                    let app_id = match app_id {
+                        // the first poll is immediate, and each one after waits for the interval
+                        Some(app_id) => {
+                            tokio::time::sleep(poll_interval).await;
+                            app_id
+                        }
+                        None => self.whoami().await?.id,
+                    };
+
+                    let status = self.get_app_training_status(&app_id).await?;
+
+                    Ok::<_, Error>((app_id, status))
+                };
+
+                match cancellation.run_until_cancelled(poll).await? {
+                    Ok((app_id, status)) => {
+                        let finished = status == TrainingStatus::Done;
+
+                        Some((Ok(status), (Some(app_id), finished)))
+                    }
+                    Err(err) => Some((Err(err), (None, true))),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,171 @@
+//! Typed values for wit's built-in (`wit$`) entities
+//!
+//! Built-in entities returned from the message endpoint have well-defined value shapes, which
+//! can be decoded from a `MessageEntity` with the methods defined here. A full list of built-in
+//! entities can be found [here](https://wit.ai/docs/built-in-entities/)
+
+use crate::message::{Coordinates, MessageEntity};
+use serde_json::Value;
+
+/// The value of a `wit$amount_of_money` entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct AmountOfMoney {
+    /// The amount of money
+    pub value: f64,
+    /// The currency of the amount. Ex. "$" or "EUR"
+    pub unit: String,
+}
+
+/// The value of a `wit$duration` entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duration {
+    /// The length of the duration, in `unit`s
+    pub value: f64,
+    /// The unit of the duration. Ex. "minute" or "day"
+    pub unit: String,
+}
+
+/// The value of a `wit$temperature` entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct Temperature {
+    /// The temperature
+    pub value: f64,
+    /// The unit of the temperature: "celsius", "fahrenheit", or "degree" when no scale was given
+    pub unit: String,
+}
+
+/// The value of a `wit$location` entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedLocation {
+    /// The name of the place. Ex. "Paris"
+    pub name: String,
+    /// The coordinates of the place, if wit resolved them
+    pub coords: Option<Coordinates>,
+    /// The kind of place, if wit resolved it. Ex. "locality", "region", or "country"
+    pub grain: Option<String>,
+}
+
+impl MessageEntity {
+    /// The numeric value and unit of this entity, if it is the built-in entity with the
+    /// given name and has a value of the expected shape
+    fn builtin_value(&self, builtin_name: &str) -> Option<(f64, String)> {
+        if self.name != builtin_name {
+            return None;
+        }
+
+        match (&self.value, &self.unit) {
+            (Some(Value::Number(value)), Some(unit)) => Some((value.as_f64()?, unit.clone())),
+            _ => None,
+        }
+    }
+
+    /// Decode the value of a `wit$amount_of_money` entity, returning None if this is a different
+    /// entity or its value does not have the expected shape (for example, when it is an interval)
+    pub fn as_amount_of_money(&self) -> Option<AmountOfMoney> {
+        let (value, unit) = self.builtin_value("wit$amount_of_money")?;
+
+        Some(AmountOfMoney { value, unit })
+    }
+
+    /// Decode the value of a `wit$duration` entity, returning None if this is a different
+    /// entity or its value does not have the expected shape
+    pub fn as_duration(&self) -> Option<Duration> {
+        let (value, unit) = self.builtin_value("wit$duration")?;
+
+        Some(Duration { value, unit })
+    }
+
+    /// Convert the value of a `wit$duration` entity to a `std::time::Duration`, returning None
+    /// if this is a different entity or its value cannot be converted (for example, when it
+    /// is negative).
+    ///
+    /// Wit's normalized value (in seconds) is used when it is present. Otherwise, the value is
+    /// converted from its unit, where a month is 30 days and a year is 365 days, as wit does
+    /// when normalizing
+    pub fn as_std_duration(&self) -> Option<std::time::Duration> {
+        if self.name != "wit$duration" {
+            return None;
+        }
+
+        let seconds = match &self.normalized {
+            Some(normalized) if normalized.unit == "second" => normalized.value,
+            _ => {
+                let duration = self.as_duration()?;
+
+                duration.value * seconds_per_unit(&duration.unit)?
+            }
+        };
+
+        std::time::Duration::try_from_secs_f64(seconds).ok()
+    }
+
+    /// Like `as_std_duration`, but returns a `chrono::Duration`
+    #[cfg(feature = "chrono")]
+    pub fn as_chrono_duration(&self) -> Option<chrono::Duration> {
+        chrono::Duration::from_std(self.as_std_duration()?).ok()
+    }
+
+    /// Decode the value of a `wit$location` entity, returning None if this is a different
+    /// entity. When wit resolved the location, its first (most likely) resolution is used for
+    /// the name, coordinates, and kind of place. Otherwise, only the name is known, taken from
+    /// the entity's value or its text
+    pub fn as_location(&self) -> Option<ResolvedLocation> {
+        if self.name != "wit$location" {
+            return None;
+        }
+
+        let resolution = self
+            .resolved
+            .as_ref()
+            .and_then(|resolved| resolved.get("values")?.as_array()?.first());
+
+        let name = resolution
+            .and_then(|resolution| resolution.get("name")?.as_str())
+            .or_else(|| self.value.as_ref()?.as_str())
+            .unwrap_or(&self.body);
+
+        let coords = resolution.and_then(|resolution| {
+            let coords = resolution.get("coords")?;
+
+            Some(Coordinates::new(
+                coords.get("lat")?.as_f64()?,
+                coords.get("long")?.as_f64()?,
+            ))
+        });
+
+        let grain = resolution
+            .and_then(|resolution| resolution.get("domain")?.as_str())
+            .map(String::from);
+
+        Some(ResolvedLocation {
+            name: String::from(name),
+            coords,
+            grain,
+        })
+    }
+
+    /// Decode the value of a `wit$temperature` entity, returning None if this is a different
+    /// entity or its value does not have the expected shape (for example, when it is an interval)
+    pub fn as_temperature(&self) -> Option<Temperature> {
+        let (value, unit) = self.builtin_value("wit$temperature")?;
+
+        Some(Temperature { value, unit })
+    }
+}
+
+/// The number of seconds in a unit of a `wit$duration` value
+fn seconds_per_unit(unit: &str) -> Option<f64> {
+    let seconds = match unit {
+        "second" => 1,
+        "minute" => 60,
+        "hour" => 60 * 60,
+        "day" => 24 * 60 * 60,
+        "week" => 7 * 24 * 60 * 60,
+        "month" => 30 * 24 * 60 * 60,
+        "quarter" => 3 * 30 * 24 * 60 * 60,
+        "year" => 365 * 24 * 60 * 60,
+        _ => return None,
+    };
+
+    Some(seconds as f64)
+}
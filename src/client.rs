@@ -1,17 +1,204 @@
 //! Contains a client struct for interacting with the wit.ai API
 
 use crate::errors::{Error, ErrorResponse};
-use reqwest::{header::ACCEPT, Method, StatusCode};
+use bytes::Bytes;
+use reqwest::{
+    header::{HeaderMap, ACCEPT, CONTENT_TYPE, USER_AGENT},
+    Method, StatusCode,
+};
 use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    cell::RefCell,
+    future::Future,
+    sync::{Arc, PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 const DEFAULT_API_HOST: &str = "https://api.wit.ai";
 
+/// The `User-Agent` header sent with every request, unless overridden with
+/// `WitClient::set_user_agent`
+pub const DEFAULT_USER_AGENT: &str = concat!("wit-ai-rs/", env!("CARGO_PKG_VERSION"));
+
+/// API versions the crate's types are meant to match, oldest first. Wit has no endpoint
+/// listing its versions: a version is any date, and requests behave as the API did on that
+/// date, so other dates work too. The crate's own tests mostly use `20231231`
+pub const KNOWN_VERSIONS: [&str; 3] = ["20230215", "20231231", "20240215"];
+
+/// The most recent of `KNOWN_VERSIONS`, used by `WitClient::new_with_latest_version`
+pub const LATEST_KNOWN_VERSION: &str = KNOWN_VERSIONS[KNOWN_VERSIONS.len() - 1];
+
+/// Response headers that announce that a request relies on something deprecated:
+/// `Deprecation` (RFC 9745) and `Sunset` (RFC 8594). `Warning` is not one of them, since it
+/// carries unrelated notices too (such as stale cached responses)
+const DEPRECATION_HEADERS: [&str; 2] = ["deprecation", "sunset"];
+
+tokio::task_local! {
+    /// Where `ResponseMeta::capture` collects the metadata of the requests made by its call
+    static CAPTURED_META: RefCell<Option<ResponseMeta>>;
+}
+
+/// Metadata about a request to the Wit API, returned alongside the response by
+/// `*_with_meta` methods and `ResponseMeta::capture`
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// The time between sending the request and receiving and parsing the full response
+    pub elapsed: Duration,
+    /// The number of times the request was retried before succeeding (see `RetryPolicy`)
+    pub retries: u32,
+    /// The HTTP status of the response
+    pub status: StatusCode,
+    /// The headers of the response, which may include rate limit information
+    pub headers: HeaderMap,
+}
+
+impl ResponseMeta {
+    /// Run `call`, which may be any of the client's typed calls, and return its result along with
+    /// the metadata of the request it made. If it made several, the metadata is that of the last
+    /// one to finish, and if it made none (ex. it failed before sending anything), it is `None`.
+    /// Streamed audio and binary requests (speech, dictation, synthesize) are not captured
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::{ResponseMeta, WitClient};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let (entities, meta) = ResponseMeta::capture(wit_client.get_entities())
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn capture<T>(
+        call: impl Future<Output = Result<T, Error>>,
+    ) -> Result<(T, Option<ResponseMeta>), Error> {
+        CAPTURED_META
+            .scope(RefCell::new(None), async {
+                let data = call.await?;
+                let meta = CAPTURED_META.with(|captured| captured.take());
+                Ok((data, meta))
+            })
+            .await
+    }
+
+    /// Every deprecation notice in the response's headers, each as `header: value` (ex.
+    /// `sunset: Wed, 31 Dec 2025 23:59:59 GMT`). Empty when wit sent none, which is usual.
+    /// The same notices are also logged as warnings when the response is received
+    pub fn deprecation_notices(&self) -> Vec<String> {
+        deprecation_notices(&self.headers)
+    }
+}
+
+/// How requests that fail with a retryable error (see `Error::is_retryable`) are retried,
+/// set with `WitClient::set_retry_policy`. By default, requests are not retried.
+///
+/// Only requests with a complete body are retried--streamed audio requests to the speech and
+/// dictation endpoints are never retried, since their body can only be sent once
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    deadline: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Create a policy that retries a failed request up to `max_retries` times, waiting 100ms
+    /// before the first retry and doubling the wait before each retry after that
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            initial_backoff: Duration::from_millis(100),
+            deadline: None,
+        }
+    }
+
+    /// Change how long to wait before the first retry. The wait doubles before each retry
+    /// after that
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Stop retrying once waiting for the next retry would take the request past `deadline`,
+    /// measured from when it was first sent. A request may still run past the deadline if
+    /// a single attempt is slow, but it is never retried after it. This is combined with
+    /// `max_retries`, so use `u32::MAX` to bound retries only by the deadline
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::RetryPolicy;
+    /// # use std::time::Duration;
+    /// let retry_policy = RetryPolicy::new(u32::MAX).deadline(Duration::from_secs(2));
+    /// ```
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// How long to wait before the retry with the given index (0 for the first retry)
+    fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+    }
+
+    /// Whether a request that has already been retried `retries` times, and has been running
+    /// for `elapsed`, should be retried again
+    fn should_retry(&self, retries: u32, elapsed: Duration) -> bool {
+        retries < self.max_retries
+            && self
+                .deadline
+                .is_none_or(|deadline| elapsed.saturating_add(self.backoff(retries)) < deadline)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// A policy that never retries
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+/// The `Accept` header sent with requests, set with `WitClient::set_accept_header`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AcceptMode {
+    /// `application/vnd.wit.<version>+json`, where the version is the client's accept version
+    #[default]
+    Versioned,
+    /// `application/json`, for proxies that reject the versioned header. Wit then relies on
+    /// the `v` query parameter alone to determine the version
+    Json,
+}
+
 /// The main struct for interacting with the Wit API
+///
+/// `WitClient` is `Send + Sync`, and the futures and streams returned by its methods are
+/// `Send`, so it can be shared between tasks on a multi-threaded runtime (for example, as
+/// state in an `axum` or `actix-web` handler). Cloning it is cheap and shares its connection
+/// pool, so a clone can be moved into each task instead of wrapping the client in an `Arc`.
+///
+/// Requests are cancelled when the future returned by a method is dropped (for example, when
+/// a web framework drops a handler because its client disconnected). No background tasks are
+/// spawned, so dropping the future closes the connection of the attempt in flight and stops
+/// any retries that `RetryPolicy` would have made. Streamed responses are cancelled in the
+/// same way when the stream is dropped.
 #[derive(Debug, Clone)]
 pub struct WitClient {
     pub(crate) api_host: String,
     version: String,
-    pub(crate) auth_token: String,
+    accept_version: Option<String>,
+    streaming_version: Option<String>,
+    accept_mode: AcceptMode,
+    // shared between clones, so that `update_token` applies to all of them
+    auth_token: Arc<RwLock<String>>,
+    pub(crate) user_agent: String,
+    slow_request_threshold: Option<Duration>,
+    retry_policy: RetryPolicy,
+    pool_max_idle_per_host: Option<usize>,
+    pool_idle_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    pub(crate) max_response_size: Option<usize>,
+    // shared between clones, so that the limit applies to all of them
+    concurrency_limit: Option<Arc<Semaphore>>,
     // reqwest stores the client in an `Arc` internally, so it can be safely cloned
     pub(crate) reqwest_client: reqwest::Client,
 }
@@ -23,35 +210,374 @@ impl WitClient {
     /// Example:
     /// ```rust
     /// # use wit_ai_rs::client::WitClient;
-    /// let wit_client = WitClient::new("TOKEN".to_string(), "20240215".to_string());
+    /// let wit_client = WitClient::new("TOKEN", "20240215");
     /// ```
-    pub fn new(auth_token: String, version: String) -> Self {
+    pub fn new(auth_token: impl Into<String>, version: impl Into<String>) -> Self {
         let api_host = String::from(DEFAULT_API_HOST);
 
         let reqwest_client = reqwest::Client::new();
 
         Self {
             api_host,
-            version,
-            auth_token,
+            version: version.into(),
+            accept_version: None,
+            streaming_version: None,
+            accept_mode: AcceptMode::default(),
+            auth_token: Arc::new(RwLock::new(auth_token.into())),
+            user_agent: String::from(DEFAULT_USER_AGENT),
+            slow_request_threshold: None,
+            retry_policy: RetryPolicy::default(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            timeout: None,
+            max_response_size: None,
+            concurrency_limit: None,
             reqwest_client,
         }
     }
 
-    /// Changes the API host--only recommended for use while testing
+    /// Create a new WitClient like `new`, using `LATEST_KNOWN_VERSION` as the version. Pinning
+    /// a version with `new` is still recommended, since this changes when the crate is
+    /// updated
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::{WitClient, LATEST_KNOWN_VERSION};
+    /// let wit_client = WitClient::new_with_latest_version("TOKEN");
+    ///
+    /// assert_eq!(wit_client.get_version(), LATEST_KNOWN_VERSION);
+    /// ```
+    pub fn new_with_latest_version(auth_token: impl Into<String>) -> Self {
+        Self::new(auth_token, LATEST_KNOWN_VERSION)
+    }
+
+    /// Changes the API host--only recommended for use while testing. Returns a
+    /// `URLParseError` if the host is not a valid URL, or an `InvalidArgument` error if it is
+    /// not an `http` or `https` URL, so that a mistake is caught here rather than on the first
+    /// request. A trailing `/` is removed
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_api_host("https://host.com")
+    ///     .unwrap();
+    /// ```
+    pub fn set_api_host(self, api_host: impl Into<String>) -> Result<Self, Error> {
+        let api_host = api_host.into();
+
+        let url = url::Url::parse(&api_host)?;
+
+        if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+            return Err(Error::InvalidArgument(format!(
+                "API host must be an http or https URL, got \"{api_host}\""
+            )));
+        }
+
+        Ok(Self {
+            api_host: api_host.trim_end_matches('/').to_owned(),
+            ..self
+        })
+    }
+
+    /// Create a new `WitClient` for a different app, using the given `auth_token` but otherwise
+    /// the same settings as this client. The new client shares this client's underlying
+    /// connection pool, which is more efficient than creating a separate client for each app.
     ///
     /// Example:
     /// ```rust
     /// # use wit_ai_rs::client::WitClient;
-    /// let wit_client = WitClient::new("TOKEN".to_string(), "20240215".to_string())
-    ///     .set_api_host("https://host.com".to_string());
+    /// let first_app = WitClient::new("FIRST_TOKEN", "20240215");
+    /// let second_app = first_app.with_token("SECOND_TOKEN");
     /// ```
-    pub fn set_api_host(self, api_host: String) -> Self {
+    pub fn with_token(&self, auth_token: impl Into<String>) -> Self {
         Self {
-            api_host,
-            auth_token: self.auth_token,
-            version: self.version,
-            reqwest_client: self.reqwest_client.clone(),
+            auth_token: Arc::new(RwLock::new(auth_token.into())),
+            ..self.clone()
+        }
+    }
+
+    /// Changes the version used in the `Accept` header, which otherwise matches the version
+    /// sent in the `v` query parameter. Some endpoints are sensitive to the `Accept` header's
+    /// version, which does not always move together with the query version.
+    ///
+    /// To override the version for a single call, set it on a clone of the client--cloning
+    /// a `WitClient` is cheap, since the underlying reqwest client is shared.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_accept_version("20231231");
+    /// ```
+    pub fn set_accept_version(self, accept_version: impl Into<String>) -> Self {
+        Self {
+            accept_version: Some(accept_version.into()),
+            ..self
+        }
+    }
+
+    /// Changes the version sent in the `v` query parameter of speech and dictation requests,
+    /// which otherwise matches the client version. Wit sometimes versions its streaming
+    /// endpoints independently of the rest of the API, so they can be pinned here.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_streaming_version("20230215");
+    /// ```
+    pub fn set_streaming_version(self, streaming_version: impl Into<String>) -> Self {
+        Self {
+            streaming_version: Some(streaming_version.into()),
+            ..self
+        }
+    }
+
+    /// Changes the format of the `Accept` header sent with requests, which defaults to
+    /// `AcceptMode::Versioned`. Use `AcceptMode::Json` when a proxy in front of wit rejects
+    /// the versioned header.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::{AcceptMode, WitClient};
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_accept_header(AcceptMode::Json);
+    /// ```
+    pub fn set_accept_header(self, accept_mode: AcceptMode) -> Self {
+        Self {
+            accept_mode,
+            ..self
+        }
+    }
+
+    /// Changes the `User-Agent` header sent with every request, which defaults to
+    /// `wit-ai-rs/<crate version>`. This can help identify your application's traffic.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_user_agent("my-app/1.0");
+    /// ```
+    pub fn set_user_agent(self, user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            ..self
+        }
+    }
+
+    /// Log a warning (using the `log` crate) for every request that takes longer than
+    /// `threshold`, including the method, endpoint, and elapsed time. This is disabled by
+    /// default.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use std::time::Duration;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_slow_request_threshold(Duration::from_secs(2));
+    /// ```
+    pub fn set_slow_request_threshold(self, threshold: Duration) -> Self {
+        Self {
+            slow_request_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Retry requests that fail with a retryable error according to `retry_policy`. The number
+    /// of retries that were needed is reported in `ResponseMeta::retries`.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::{RetryPolicy, WitClient};
+    /// # use std::time::Duration;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_retry_policy(RetryPolicy::new(3).initial_backoff(Duration::from_millis(250)));
+    /// ```
+    pub fn set_retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Reject any response body larger than `max_size` bytes with `Error::ResponseTooLarge`,
+    /// rather than reading it all into memory. This protects against a misbehaving proxy
+    /// returning an enormous body. By default, there is no limit.
+    ///
+    /// The speech, dictation, and streamed utterance endpoints may send any number of results,
+    /// so for them the limit applies to each result rather than to the whole response.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215").set_max_response_size(1024 * 1024);
+    /// ```
+    pub fn set_max_response_size(self, max_size: usize) -> Self {
+        Self {
+            max_response_size: Some(max_size),
+            ..self
+        }
+    }
+
+    /// Limits how many requests can be in flight at once, across every method and every clone
+    /// of the client, to avoid tripping wit's rate limits. Requests over the limit wait for an
+    /// earlier one to finish before being sent. By default, there is no limit.
+    ///
+    /// Each attempt of a request holds a slot until its response has been read, but not while
+    /// waiting to retry. Streamed requests (speech, dictation, and streamed utterances) hold a
+    /// slot only until their response starts arriving.
+    ///
+    /// Returns an `InvalidArgument` error if `max_requests` is 0, since no request could ever
+    /// be sent.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_max_concurrent_requests(4)
+    ///     .unwrap();
+    /// ```
+    pub fn set_max_concurrent_requests(self, max_requests: usize) -> Result<Self, Error> {
+        if max_requests == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "max_requests must be at least 1",
+            )));
+        }
+
+        Ok(Self {
+            concurrency_limit: Some(Arc::new(Semaphore::new(max_requests))),
+            ..self
+        })
+    }
+
+    /// Limits how many idle connections to each host are kept open for reuse. By default,
+    /// reqwest does not limit this.
+    ///
+    /// This replaces the underlying connection pool, so set it before cloning the client or
+    /// calling `with_token` if the clones should share the pool.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("TOKEN", "20240215").set_pool_max_idle_per_host(32);
+    /// ```
+    pub fn set_pool_max_idle_per_host(self, max_idle: usize) -> Self {
+        Self {
+            pool_max_idle_per_host: Some(max_idle),
+            ..self
+        }
+        .rebuild_reqwest_client()
+    }
+
+    /// Changes how long an idle connection is kept open for reuse before it is closed, which
+    /// reqwest defaults to 90 seconds. Like `set_pool_max_idle_per_host`, this replaces the
+    /// underlying connection pool.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use std::time::Duration;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_pool_idle_timeout(Duration::from_secs(30));
+    /// ```
+    pub fn set_pool_idle_timeout(self, idle_timeout: Duration) -> Self {
+        Self {
+            pool_idle_timeout: Some(idle_timeout),
+            ..self
+        }
+        .rebuild_reqwest_client()
+    }
+
+    /// Fail requests that take longer than `timeout` to complete, from connecting until the
+    /// response has been read, with a `RequestError`. By default, requests have no timeout.
+    /// Each retry gets the full timeout, so use `RetryPolicy::deadline` to bound the total.
+    /// Streamed speech and dictation requests are included, so allow for the longest audio
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use std::time::Duration;
+    /// let wit_client = WitClient::new("TOKEN", "20240215")
+    ///     .set_timeout(Duration::from_secs(10));
+    /// ```
+    pub fn set_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
+        }
+        .rebuild_reqwest_client()
+    }
+
+    /// Replace the reqwest client with one built from the current connection settings
+    fn rebuild_reqwest_client(self) -> Self {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max_idle);
+        }
+
+        if let Some(idle_timeout) = self.pool_idle_timeout {
+            builder = builder.pool_idle_timeout(idle_timeout);
+        }
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        // `reqwest::Client::new` panics in the same situation, which only happens if the TLS
+        // backend cannot be initialized
+        let reqwest_client = builder.build().expect("failed to build the reqwest client");
+
+        Self {
+            reqwest_client,
+            ..self
+        }
+    }
+
+    /// Replace the token used to authenticate requests, for example when it is rotated. Unlike
+    /// creating a new client, this keeps the existing connection pool.
+    ///
+    /// The new token is used by this client and every clone of it, but not by clients created
+    /// with `with_token`. Requests that have already started keep using the previous token,
+    /// including any retries of them.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::client::WitClient;
+    /// let wit_client = WitClient::new("OLD_TOKEN", "20240215");
+    ///
+    /// wit_client.update_token("NEW_TOKEN");
+    /// ```
+    pub fn update_token(&self, auth_token: impl Into<String>) {
+        // a poisoned lock still holds a complete token, since writing a `String` cannot panic
+        // part way through
+        *self
+            .auth_token
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = auth_token.into();
+    }
+
+    /// The token currently used to authenticate requests
+    pub(crate) fn auth_token(&self) -> String {
+        self.auth_token
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Wait for a slot under the limit set with `set_max_concurrent_requests`, if any. The
+    /// slot is released when the returned permit is dropped
+    pub(crate) async fn acquire_request_permit(&self) -> Option<SemaphorePermit<'_>> {
+        match &self.concurrency_limit {
+            Some(semaphore) => Some(
+                semaphore
+                    .acquire()
+                    .await
+                    .expect("the request semaphore is never closed"),
+            ),
+            None => None,
         }
     }
 
@@ -62,40 +588,430 @@ impl WitClient {
         url_params: Vec<(String, String)>,
         body: Option<impl Serialize>,
     ) -> Result<T, Error> {
+        let (data, _) = self
+            .make_request_with_meta(method, endpoint, url_params, body)
+            .await?;
+
+        Ok(data)
+    }
+
+    /// Like `make_request`, but also returns metadata about the request
+    pub(crate) async fn make_request_with_meta<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        url_params: Vec<(String, String)>,
+        body: Option<impl Serialize>,
+    ) -> Result<(T, ResponseMeta), Error> {
+        let start = Instant::now();
+
         let url = format!("{}{endpoint}?v={}", self.api_host, self.version);
 
-        let mut request = match method {
-            Method::GET => self.reqwest_client.get(url),
-            Method::POST => self.reqwest_client.post(url),
-            Method::DELETE => self.reqwest_client.delete(url),
-            Method::PUT => self.reqwest_client.put(url),
-            _ => panic!("invalid method passed to internal `make_request` method"),
+        // read once, so that every attempt uses the same token even if it is updated meanwhile
+        let auth_token = self.auth_token();
+
+        // the body is serialized once so that the same bytes can be sent on every attempt
+        let body = body
+            .map(|body| serde_json::to_vec(&body).map(Bytes::from))
+            .transpose()
+            .map_err(|err| Error::JSONParseError(err.to_string()))?;
+
+        let accept = self.accept_header();
+
+        let ((data, status, headers), retries) = self
+            .with_retries(|| {
+                self.send_request(
+                    method.clone(),
+                    &url,
+                    &url_params,
+                    &auth_token,
+                    &accept,
+                    body.clone(),
+                )
+            })
+            .await?;
+
+        let meta = ResponseMeta {
+            elapsed: start.elapsed(),
+            retries,
+            status,
+            headers,
         };
 
-        request = request.query(&url_params);
+        // outside of `ResponseMeta::capture` there is nowhere to record it, which is fine
+        let _ = CAPTURED_META.try_with(|captured| captured.replace(Some(meta.clone())));
+
+        if let Some(threshold) = self.slow_request_threshold {
+            if meta.elapsed > threshold {
+                log::warn!(
+                    "slow request: {method} {endpoint} took {:?} (threshold {threshold:?})",
+                    meta.elapsed
+                );
+            }
+        }
+
+        Ok((data, meta))
+    }
+
+    /// Like `make_request`, but sends `accept` as the `Accept` header and returns the raw bytes
+    /// of the response along with its headers, for endpoints that do not respond with JSON
+    pub(crate) async fn make_binary_request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        url_params: Vec<(String, String)>,
+        body: Option<impl Serialize>,
+        accept: &str,
+    ) -> Result<(Vec<u8>, HeaderMap), Error> {
+        let url = format!("{}{endpoint}?v={}", self.api_host, self.version);
+
+        let auth_token = self.auth_token();
+
+        let body = body
+            .map(|body| serde_json::to_vec(&body).map(Bytes::from))
+            .transpose()
+            .map_err(|err| Error::JSONParseError(err.to_string()))?;
+
+        let (response, _) = self
+            .with_retries(|| async {
+                let _permit = self.acquire_request_permit().await;
+
+                let response = self
+                    .send_raw(
+                        method.clone(),
+                        &url,
+                        &url_params,
+                        &auth_token,
+                        accept,
+                        body.clone(),
+                    )
+                    .await?;
+
+                let headers = response.headers().clone();
+
+                Ok((read_body(response, self.max_response_size).await?, headers))
+            })
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Run `attempt` until it succeeds, fails with an error that is not retryable, or the
+    /// client's `RetryPolicy` runs out of retries or time, returning its result along with the number
+    /// of retries made
+    async fn with_retries<T, F: Future<Output = Result<T, Error>>>(
+        &self,
+        attempt: impl Fn() -> F,
+    ) -> Result<(T, u32), Error> {
+        let start = Instant::now();
+        let mut retries = 0;
+
+        loop {
+            match attempt().await {
+                Err(error)
+                    if error.is_retryable()
+                        && self.retry_policy.should_retry(retries, start.elapsed()) =>
+                {
+                    tokio::time::sleep(self.retry_policy.backoff(retries)).await;
+                    retries += 1;
+                }
+                result => return result.map(|data| (data, retries)),
+            }
+        }
+    }
+
+    /// Make a single attempt at a request, returning the parsed response along with its
+    /// status and headers
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        url: &str,
+        url_params: &[(String, String)],
+        auth_token: &str,
+        accept: &str,
+        body: Option<Bytes>,
+    ) -> Result<(T, StatusCode, HeaderMap), Error> {
+        let _permit = self.acquire_request_permit().await;
+
+        let response = self
+            .send_raw(method, url, url_params, auth_token, accept, body)
+            .await?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+
+        let data = parse_body(read_body(response, self.max_response_size).await?).await?;
+
+        Ok((data, status, headers))
+    }
+
+    /// Make a single attempt at a request, returning the response without reading its body if
+    /// it succeeded, or the error returned by wit otherwise
+    async fn send_raw(
+        &self,
+        method: Method,
+        url: &str,
+        url_params: &[(String, String)],
+        auth_token: &str,
+        accept: &str,
+        body: Option<Bytes>,
+    ) -> Result<reqwest::Response, Error> {
+        let mut request = self.reqwest_client.request(method, url).query(url_params);
 
         request = match body {
-            // .json() internally sets the content type header to application/json
-            Some(body) => request.json(&body),
+            Some(body) => request.header(CONTENT_TYPE, "application/json").body(body),
             None => request,
         };
 
         let response = request
-            .bearer_auth(&self.auth_token)
-            .header(ACCEPT, format!("application/vnd.wit.{}+json", self.version))
+            .bearer_auth(auth_token)
+            .header(USER_AGENT, &self.user_agent)
+            .header(ACCEPT, accept)
             .send()
             .await?;
 
-        let data = match response.status() {
-            StatusCode::OK => Ok(response.json::<T>().await?),
-            _ => Err(response.json::<ErrorResponse>().await?),
-        }?;
+        log_deprecation_notices(&response);
+
+        let status = response.status();
+
+        match status {
+            StatusCode::OK | StatusCode::NO_CONTENT => Ok(response),
+            _ => {
+                let body = read_body(response, self.max_response_size).await?;
+
+                let mut error: ErrorResponse = parse_json(body).await?;
+                error.status = Some(status);
+                Err(error.into())
+            }
+        }
+    }
+
+    /// Like `make_request`, but returns the response as soon as it starts arriving, so that
+    /// its body can be read incrementally. Requests made this way are not retried
+    pub(crate) async fn make_streaming_request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        url_params: Vec<(String, String)>,
+    ) -> Result<reqwest::Response, Error> {
+        let url = format!("{}{endpoint}?v={}", self.api_host, self.version);
+
+        let _permit = self.acquire_request_permit().await;
+
+        self.send_raw(
+            method,
+            &url,
+            &url_params,
+            &self.auth_token(),
+            &self.accept_header(),
+            None,
+        )
+        .await
+    }
+
+    /// Send a request to an endpoint that responds with no body, such as an endpoint that
+    /// this crate does not yet have a dedicated method for. Succeeds on a `200` or `204`
+    /// status, ignoring any body that is returned.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use reqwest::Method;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// wit_client
+    ///     .request_empty(Method::DELETE, "/intents/some_intent", vec![], Option::<()>::None)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn request_empty(
+        &self,
+        method: Method,
+        endpoint: &str,
+        url_params: Vec<(String, String)>,
+        body: Option<impl Serialize>,
+    ) -> Result<(), Error> {
+        self.make_request::<serde::de::IgnoredAny>(method, endpoint, url_params, body)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Send a request to an endpoint that responds with something other than JSON (such as
+    /// audio or an archive), returning the raw bytes of the response. `accept` is sent as the
+    /// `Accept` header in place of the client's usual JSON one, while authentication, the API
+    /// version, and retries are handled as for every other request.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use reqwest::Method;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let audio: Vec<u8> = wit_client
+    ///     .request_bytes(
+    ///         Method::POST,
+    ///         "/synthesize",
+    ///         vec![],
+    ///         Some(serde_json::json!({"q": "hello", "voice": "Rebecca"})),
+    ///         "audio/mpeg",
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn request_bytes(
+        &self,
+        method: Method,
+        endpoint: &str,
+        url_params: Vec<(String, String)>,
+        body: Option<impl Serialize>,
+        accept: &str,
+    ) -> Result<Vec<u8>, Error> {
+        let (data, _) = self
+            .make_binary_request(method, endpoint, url_params, body, accept)
+            .await?;
 
         Ok(data)
     }
 
+    /// The value of the `Accept` header, according to the client's `AcceptMode`
+    fn accept_header(&self) -> String {
+        match self.accept_mode {
+            AcceptMode::Versioned => {
+                format!("application/vnd.wit.{}+json", self.get_accept_version())
+            }
+            AcceptMode::Json => String::from("application/json"),
+        }
+    }
+
+    /// The length of the full URL that `make_request` would send to the given endpoint
+    /// with the given URL parameters
+    pub(crate) fn url_length(&self, endpoint: &str, url_params: &[(String, String)]) -> usize {
+        let base_url = format!("{}{endpoint}?v={}", self.api_host, self.version);
+
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(url_params)
+            .finish();
+
+        // parameters are joined to the base url with an extra `&`
+        base_url.len() + if query.is_empty() { 0 } else { query.len() + 1 }
+    }
+
     /// Getter for `WitClient` version
     pub fn get_version(&self) -> &str {
         &self.version
     }
+
+    /// Getter for the version used by speech and dictation requests, which is the same as the
+    /// `WitClient` version unless it has been overridden with `set_streaming_version`
+    pub fn get_streaming_version(&self) -> &str {
+        self.streaming_version.as_deref().unwrap_or(&self.version)
+    }
+
+    /// Getter for the limit on idle connections per host set with
+    /// `set_pool_max_idle_per_host`, if any
+    pub fn get_pool_max_idle_per_host(&self) -> Option<usize> {
+        self.pool_max_idle_per_host
+    }
+
+    /// Getter for the idle connection timeout set with `set_pool_idle_timeout`, if any
+    pub fn get_pool_idle_timeout(&self) -> Option<Duration> {
+        self.pool_idle_timeout
+    }
+
+    /// Getter for the version used in the `Accept` header, which is the same as the
+    /// `WitClient` version unless it has been overridden with `set_accept_version`
+    pub fn get_accept_version(&self) -> &str {
+        self.accept_version.as_deref().unwrap_or(&self.version)
+    }
+}
+
+/// The deprecation notices in `headers`, each as `header: value`. Values that are not valid
+/// UTF-8 are skipped
+fn deprecation_notices(headers: &HeaderMap) -> Vec<String> {
+    DEPRECATION_HEADERS
+        .iter()
+        .flat_map(|name| {
+            headers
+                .get_all(*name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .map(move |value| format!("{name}: {value}"))
+        })
+        .collect()
+}
+
+/// An endpoint path made of the given segments, each percent-encoded so that characters such
+/// as `/`, `?`, and `#` stay part of the segment (ex. `["traits", "a/b"]` becomes
+/// `/traits/a%2Fb`)
+pub(crate) fn encode_path(segments: &[&str]) -> String {
+    let mut url = url::Url::parse("http://localhost").expect("the base URL is valid");
+
+    url.path_segments_mut()
+        .expect("an http URL can have a path")
+        .clear()
+        .extend(segments);
+
+    url.path().to_owned()
+}
+
+/// Log each deprecation notice in the headers of `response` as a warning
+pub(crate) fn log_deprecation_notices(response: &reqwest::Response) {
+    for notice in deprecation_notices(response.headers()) {
+        log::warn!(
+            "deprecation notice from wit for {}: {notice}",
+            response.url().path()
+        );
+    }
+}
+
+/// Read the whole body of a response, failing as soon as it is known to be larger than
+/// `max_size` bytes
+async fn read_body(
+    mut response: reqwest::Response,
+    max_size: Option<usize>,
+) -> Result<Vec<u8>, Error> {
+    let Some(max_size) = max_size else {
+        return Ok(response.bytes().await?.to_vec());
+    };
+
+    if response
+        .content_length()
+        .is_some_and(|length| length > max_size as u64)
+    {
+        return Err(Error::ResponseTooLarge(max_size));
+    }
+
+    let mut body = Vec::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_size {
+            return Err(Error::ResponseTooLarge(max_size));
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(body)
+}
+
+/// Parse a successful response body, treating an empty body (as sent with a `204` status) as
+/// JSON `null` so that types which can represent nothing (such as `()` or an `Option`) are
+/// parsed successfully. A body that is not valid JSON for `T` is a `ResponseParseError`
+async fn parse_body<T: DeserializeOwned>(body: Vec<u8>) -> Result<T, Error> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return parse_json(b"null".to_vec()).await;
+    }
+
+    parse_json(body).await
+}
+
+/// Parse a body that has already been read, with the same errors as `reqwest::Response::json`
+async fn parse_json<T: DeserializeOwned>(body: Vec<u8>) -> Result<T, Error> {
+    reqwest::Response::from(http::Response::new(body))
+        .json()
+        .await
+        .map_err(Error::ResponseParseError)
 }
@@ -3,10 +3,20 @@
 //! Types specific to each endpoint are stored in the module relating to that endpoint, but
 //! here are types that are used in or returned from multiple endpoints.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use futures::{Stream, TryStreamExt};
+use reqwest::Body;
 use serde::{Deserialize, Serialize};
 
+use crate::errors::Error;
+
+/// The approximate maximum length, in bytes, of serialized dynamic entities that wit will accept.
+/// Dynamic entities are serialized to JSON and sent in the request URL, and wit does not publish
+/// an exact limit for them, so this is a conservative upper bound--anything larger is almost
+/// certain to be rejected with an unhelpful error, so requests exceeding it fail early instead
+pub const MAX_DYNAMIC_ENTITIES_LENGTH: usize = 64 * 1024;
+
 /// The response returned when deleting an object
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct DeleteResponse {
@@ -15,34 +25,93 @@ pub struct DeleteResponse {
 }
 
 /// Basic information about a trait
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
 pub struct TraitBasic {
     /// The trait id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The trait name
     pub name: String,
 }
 
 /// Basic information about an intent
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
 pub struct IntentBasic {
     /// The intent id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The intent name
     pub name: String,
 }
 
 /// Basic information about an entity
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
 pub struct EntityBasic {
     /// The entity id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The entity name
     pub name: String,
 }
 
+/// Wit's confidence in a result, which is always between 0.0 and 1.0 inclusive (and never NaN).
+///
+/// When deserializing, out-of-range values are clamped into range (and a warning is logged)
+/// rather than failing the whole response, while NaN is rejected
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Confidence(f64);
+
+impl Confidence {
+    /// Create a new `Confidence`, returning an error if `value` is not between 0.0 and 1.0
+    /// inclusive
+    pub fn new(value: f64) -> Result<Self, Error> {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(Error::InvalidArgument(format!(
+                "confidence must be between 0.0 and 1.0 inclusive, got {value}"
+            )));
+        }
+
+        Ok(Self(value))
+    }
+
+    /// The confidence as a raw `f64`
+    pub fn into_inner(self) -> f64 {
+        self.0
+    }
+}
+
+impl From<Confidence> for f64 {
+    fn from(confidence: Confidence) -> Self {
+        confidence.0
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<'de> Deserialize<'de> for Confidence {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+
+        if value.is_nan() {
+            return Err(serde::de::Error::custom("confidence must not be NaN"));
+        }
+
+        let clamped = value.clamp(0.0, 1.0);
+
+        if clamped != value {
+            log::warn!("wit returned a confidence of {value}, which was clamped to {clamped}");
+        }
+
+        Ok(Self(clamped))
+    }
+}
+
 /// Keywords associated with entities that may be extracted from text
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EntityKeyword {
     /// Canonical value of the entity.
     pub keyword: String,
@@ -52,8 +121,19 @@ pub struct EntityKeyword {
 
 impl EntityKeyword {
     /// Create a new Keyword struct
-    pub fn new(keyword: String, synonyms: Vec<String>) -> Self {
-        Self { keyword, synonyms }
+    pub fn new(keyword: impl Into<String>, synonyms: Vec<String>) -> Self {
+        Self {
+            keyword: keyword.into(),
+            synonyms,
+        }
+    }
+
+    /// Remove repeated synonyms, keeping the first occurrence of each so that the order is
+    /// otherwise unchanged. Wit does the same when it stores a keyword
+    pub fn dedup_synonyms(&mut self) {
+        let mut seen = HashSet::new();
+
+        self.synonyms.retain(|synonym| seen.insert(synonym.clone()));
     }
 }
 
@@ -67,52 +147,510 @@ pub struct DynamicEntity {
 impl DynamicEntity {
     /// Creates a new dynamic entity with the given name and keywords. Note that
     /// dynamic entities can only be used to extend existing keyword entities.
-    pub fn new(name: String, keywords: Vec<EntityKeyword>) -> Self {
-        Self { name, keywords }
+    pub fn new(name: impl Into<String>, keywords: Vec<EntityKeyword>) -> Self {
+        Self {
+            name: name.into(),
+            keywords,
+        }
     }
 }
 
 /// One or many dynamic entities to be passed with a request
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DynamicEntities {
-    entities: HashMap<String, Vec<EntityKeyword>>,
+    // ordered so that the same entities always serialize the same way
+    entities: BTreeMap<String, Vec<EntityKeyword>>,
 }
 
 impl DynamicEntities {
     /// Creates a new DynamicEntities object to be included in a request, given
-    /// some dynamic entities
+    /// some dynamic entities. Entities that share a name are merged rather than
+    /// overwritten (see `extend`)
     pub fn new(entities: Vec<DynamicEntity>) -> Self {
-        let mut entities_map: HashMap<String, Vec<EntityKeyword>> = HashMap::new();
+        entities.into_iter().collect()
+    }
+
+    /// Creates a new DynamicEntities object from a map of entity names to their keywords.
+    /// This serializes identically to passing the equivalent `DynamicEntity` values to `new`
+    pub fn from_map(entities: HashMap<String, Vec<EntityKeyword>>) -> Self {
+        entities
+            .into_iter()
+            .map(|(name, keywords)| DynamicEntity::new(name, keywords))
+            .collect()
+    }
+
+    /// Creates a new DynamicEntities object from entity names and their keywords, where each
+    /// keyword's only synonym is the keyword itself. Entities that share a name are merged
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::common_types::DynamicEntities;
+    /// let entities = DynamicEntities::from_simple(&[("contact", &["Alice", "Bob"])]);
+    /// ```
+    pub fn from_simple(entities: &[(&str, &[&str])]) -> Self {
+        entities
+            .iter()
+            .map(|(name, keywords)| {
+                let keywords = keywords
+                    .iter()
+                    .map(|keyword| EntityKeyword::new(*keyword, vec![keyword.to_string()]))
+                    .collect();
+
+                DynamicEntity::new(*name, keywords)
+            })
+            .collect()
+    }
+
+    /// Merge a single dynamic entity into this collection. If an entity with the same name
+    /// already exists, its keywords are combined, and synonyms of keywords that share a
+    /// canonical value are combined as well
+    fn insert(&mut self, entity: DynamicEntity) {
+        let keywords = self.entities.entry(entity.name).or_default();
 
-        for entity in entities {
-            entities_map.insert(entity.name, entity.keywords);
+        for keyword in entity.keywords {
+            match keywords
+                .iter_mut()
+                .find(|existing| existing.keyword == keyword.keyword)
+            {
+                Some(existing) => {
+                    for synonym in keyword.synonyms {
+                        if !existing.synonyms.contains(&synonym) {
+                            existing.synonyms.push(synonym);
+                        }
+                    }
+                }
+                None => keywords.push(keyword),
+            }
         }
+    }
 
-        Self {
-            entities: entities_map,
+    /// The names of the entities these dynamic entities extend
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.entities.keys().map(String::as_str)
+    }
+
+    /// Serialize the dynamic entities, returning an error if the result is larger than
+    /// `MAX_DYNAMIC_ENTITIES_LENGTH`
+    pub(crate) fn get_serialized(&self) -> Result<String, Error> {
+        let serialized =
+            serde_json::to_string(&self).expect("should be able to serialize DynamicEntities");
+
+        if serialized.len() > MAX_DYNAMIC_ENTITIES_LENGTH {
+            return Err(Error::InvalidArgument(format!(
+                "serialized dynamic entities are {} bytes, which exceeds the approximate limit of \
+                {MAX_DYNAMIC_ENTITIES_LENGTH} bytes--try reducing the number of keywords and synonyms",
+                serialized.len()
+            )));
         }
+
+        Ok(serialized)
     }
+}
 
-    pub(crate) fn get_serialized(&self) -> String {
-        serde_json::to_string(&self).expect("should be able to serialize DynamicEntities")
+impl From<HashMap<String, Vec<EntityKeyword>>> for DynamicEntities {
+    fn from(entities: HashMap<String, Vec<EntityKeyword>>) -> Self {
+        Self::from_map(entities)
+    }
+}
+
+impl FromIterator<DynamicEntity> for DynamicEntities {
+    fn from_iter<I: IntoIterator<Item = DynamicEntity>>(iter: I) -> Self {
+        let mut dynamic_entities = Self {
+            entities: BTreeMap::new(),
+        };
+
+        dynamic_entities.extend(iter);
+
+        dynamic_entities
+    }
+}
+
+impl Extend<DynamicEntity> for DynamicEntities {
+    /// Add more dynamic entities, merging them into any existing entities with the same name
+    fn extend<I: IntoIterator<Item = DynamicEntity>>(&mut self, iter: I) {
+        for entity in iter {
+            self.insert(entity);
+        }
     }
 }
 
 /// The audio type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AudioType {
     /// MP3 (files ending in .mp3, for example)
     MP3,
     /// WAV (files ending in .wav, for example)
     /// NOTE: this format is not streamable, which will slow down
-    /// dictation speed
+    /// dictation speed, so a warning is logged the first time it is used
     WAV,
+    /// Raw PCM audio with no header, such as samples captured directly from a microphone,
+    /// in the format described by the config (see `RawAudioConfigBuilder`)
+    Raw(RawAudioConfig),
+}
+
+impl AudioType {
+    /// Log a warning, once per process, if this audio type is not streamable
+    pub(crate) fn warn_if_not_streamable(&self) {
+        static WAV_WARNING: std::sync::Once = std::sync::Once::new();
+
+        if let Self::WAV = self {
+            WAV_WARNING.call_once(|| {
+                log::warn!(
+                    "WAV audio is not streamable, so wit must receive the whole file before \
+                    processing it--use MP3 or raw PCM for lower latency"
+                )
+            });
+        }
+    }
+}
+
+/// Formats the audio type as its MIME type, which is sent as the `Content-Type` of audio
+/// requests and can be parsed back with `FromStr`
+impl std::fmt::Display for AudioType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MP3 => f.write_str("audio/mpeg"),
+            Self::WAV => f.write_str("audio/wav"),
+            Self::Raw(config) => write!(
+                f,
+                "audio/raw;encoding={};bits={};rate={};endian={}",
+                config.encoding.as_str(),
+                config.bits,
+                config.sample_rate,
+                config.endianness.as_str()
+            ),
+        }
+    }
+}
+
+/// Parses an audio type from a file extension (`"mp3"`, `"wav"`) or a MIME type
+/// (`"audio/mpeg"`, `"audio/wav"`), ignoring case, so that it can be read from a config file
+/// or command line flag. Raw audio is parsed from its full content type (as produced by
+/// `Display`), or from `"raw"` or `"audio/pcm16"` (the type of raw audio synthesized by wit)
+/// for the default `RawAudioConfig`
+///
+/// Example:
+/// ```rust
+/// # use wit_ai_rs::common_types::AudioType;
+/// let audio_type: AudioType = "MP3".parse().unwrap();
+///
+/// assert_eq!(audio_type, AudioType::MP3);
+/// assert_eq!(audio_type.to_string().parse::<AudioType>().unwrap(), AudioType::MP3);
+/// ```
+impl std::str::FromStr for AudioType {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "mp3" | "mpeg" | "audio/mpeg" | "audio/mp3" => Ok(Self::MP3),
+            "wav" | "wave" | "audio/wav" | "audio/wave" | "audio/x-wav" => Ok(Self::WAV),
+            "raw" | "audio/pcm16" => Ok(Self::Raw(RawAudioConfig::default())),
+            lowercase => match lowercase.strip_prefix("audio/raw;") {
+                Some(params) => RawAudioConfig::from_content_type_params(params).map(Self::Raw),
+                None => Err(Error::InvalidArgument(format!(
+                    "unsupported audio type {value:?}, expected mp3, wav, or raw"
+                ))),
+            },
+        }
+    }
+}
+
+/// How the samples of raw audio are encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawEncoding {
+    /// Signed integer samples (the most common PCM encoding)
+    SignedInteger,
+    /// Unsigned integer samples
+    UnsignedInteger,
+    /// 32-bit floating point samples
+    FloatingPoint,
+    /// 8-bit μ-law companded samples, common in telephony
+    MuLaw,
+    /// 8-bit A-law companded samples, common in telephony
+    ALaw,
+}
+
+impl RawEncoding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::SignedInteger => "signed-integer",
+            Self::UnsignedInteger => "unsigned-integer",
+            Self::FloatingPoint => "floating-point",
+            Self::MuLaw => "mu-law",
+            Self::ALaw => "a-law",
+        }
+    }
+}
+
+/// The byte order of raw audio samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// Least significant byte first, as produced by most hardware
+    Little,
+    /// Most significant byte first
+    Big,
+}
+
+impl Endianness {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Little => "little",
+            Self::Big => "big",
+        }
+    }
+}
+
+/// The format of raw PCM audio, built with `RawAudioConfigBuilder` so that only formats
+/// wit accepts can be sent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RawAudioConfig {
+    encoding: RawEncoding,
+    bits: u8,
+    sample_rate: u32,
+    endianness: Endianness,
+}
+
+impl RawAudioConfig {
+    /// The encoding of the samples
+    pub fn encoding(&self) -> RawEncoding {
+        self.encoding
+    }
+
+    /// The number of bits in each sample
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// The number of samples per second
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// The byte order of the samples
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// Parse the parameters of a raw audio content type (everything after `audio/raw;`)
+    fn from_content_type_params(params: &str) -> Result<Self, Error> {
+        let mut builder = RawAudioConfigBuilder::new();
+
+        for param in params.split(';') {
+            let (name, value) = param.trim().split_once('=').ok_or_else(|| {
+                Error::InvalidArgument(format!("invalid raw audio parameter {param:?}"))
+            })?;
+
+            let invalid =
+                || Error::InvalidArgument(format!("invalid value for raw audio {name}: {value:?}"));
+
+            builder = match name {
+                "encoding" => builder.encoding(match value {
+                    "signed-integer" => RawEncoding::SignedInteger,
+                    "unsigned-integer" => RawEncoding::UnsignedInteger,
+                    "floating-point" => RawEncoding::FloatingPoint,
+                    "mu-law" => RawEncoding::MuLaw,
+                    "a-law" => RawEncoding::ALaw,
+                    _ => return Err(invalid()),
+                }),
+                "bits" => builder.bits(value.parse().map_err(|_| invalid())?),
+                "rate" => builder.sample_rate(value.parse().map_err(|_| invalid())?),
+                "endian" => builder.endianness(match value {
+                    "little" => Endianness::Little,
+                    "big" => Endianness::Big,
+                    _ => return Err(invalid()),
+                }),
+                _ => {
+                    return Err(Error::InvalidArgument(format!(
+                        "unknown raw audio parameter {name:?}"
+                    )))
+                }
+            };
+        }
+
+        builder.build()
+    }
+}
+
+impl Default for RawAudioConfig {
+    /// 16-bit signed little-endian samples at 16kHz, the format wit recommends
+    fn default() -> Self {
+        Self {
+            encoding: RawEncoding::SignedInteger,
+            bits: 16,
+            sample_rate: 16_000,
+            endianness: Endianness::Little,
+        }
+    }
+}
+
+/// Builder for `RawAudioConfig`, which checks that the format is one that wit accepts: mono
+/// audio at 8kHz or 16kHz, with 8, 16, or 32-bit integer samples, 32-bit floating point
+/// samples, or 8-bit μ-law or A-law samples. Wit does not resample unsupported formats and may
+/// return empty results for them, so audio captured in another format (ex. 44.1kHz stereo
+/// from a microphone) should be downmixed and resampled to 16kHz mono before sending.
+///
+/// Example:
+/// ```rust
+/// # use wit_ai_rs::common_types::{AudioType, Endianness, RawAudioConfigBuilder};
+/// let config = RawAudioConfigBuilder::new()
+///     .sample_rate(8_000)
+///     .endianness(Endianness::Big)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     AudioType::Raw(config).to_string(),
+///     "audio/raw;encoding=signed-integer;bits=16;rate=8000;endian=big"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawAudioConfigBuilder {
+    config: RawAudioConfig,
+    channels: u8,
+}
+
+impl RawAudioConfigBuilder {
+    /// Sample rates wit accepts for raw audio
+    const SAMPLE_RATES: [u32; 2] = [8_000, 16_000];
+
+    /// Create a builder starting from the default format, 16-bit signed little-endian mono
+    /// samples at 16kHz
+    pub fn new() -> Self {
+        Self {
+            config: RawAudioConfig::default(),
+            channels: 1,
+        }
+    }
+
+    /// Set the encoding of the samples
+    pub fn encoding(mut self, encoding: RawEncoding) -> Self {
+        self.config.encoding = encoding;
+        self
+    }
+
+    /// Set the number of bits in each sample
+    pub fn bits(mut self, bits: u8) -> Self {
+        self.config.bits = bits;
+        self
+    }
+
+    /// Set the number of samples per second, in Hz
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.config.sample_rate = sample_rate;
+        self
+    }
+
+    /// Set the number of channels. Wit only accepts mono audio, so anything other than 1 is
+    /// rejected by `build`
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Set the byte order of the samples
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+
+    /// Create the `RawAudioConfig`, or an `Error::InvalidArgument` describing the formats wit
+    /// accepts if this one is not among them
+    pub fn build(self) -> Result<RawAudioConfig, Error> {
+        let config = self.config;
+
+        if self.channels != 1 {
+            return Err(Error::InvalidArgument(format!(
+                "wit only accepts mono raw audio, got {} channels",
+                self.channels
+            )));
+        }
+
+        if !Self::SAMPLE_RATES.contains(&config.sample_rate) {
+            return Err(Error::InvalidArgument(format!(
+                "wit accepts raw audio at 8000 or 16000 Hz, got {} Hz",
+                config.sample_rate
+            )));
+        }
+
+        let supported_bits: &[u8] = match config.encoding {
+            RawEncoding::SignedInteger | RawEncoding::UnsignedInteger => &[8, 16, 32],
+            RawEncoding::FloatingPoint => &[32],
+            RawEncoding::MuLaw | RawEncoding::ALaw => &[8],
+        };
+
+        if !supported_bits.contains(&config.bits) {
+            return Err(Error::InvalidArgument(format!(
+                "wit accepts {} raw audio with {:?} bits per sample, got {}",
+                config.encoding.as_str(),
+                supported_bits,
+                config.bits
+            )));
+        }
+
+        Ok(config)
+    }
+}
+
+impl Default for RawAudioConfigBuilder {
+    /// The same as `RawAudioConfigBuilder::new`
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Audio data that reports its upload progress as it is streamed to wit, for use with the
+/// speech and dictation endpoints
+pub struct ProgressBody {
+    body: Body,
+}
+
+impl ProgressBody {
+    /// Wrap a stream of audio data chunks so that `on_progress` is called with the total number of
+    /// bytes sent so far each time a chunk is handed off to be sent. If the total size of the
+    /// audio is known (for example, from a file's metadata), this can be used to compute a
+    /// percentage for a progress bar.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::{AudioType, ProgressBody};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let audio = std::fs::read("test.mp3").unwrap();
+    /// let total = audio.len() as u64;
+    ///
+    /// let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+    ///     audio.chunks(4096).map(|chunk| Ok(chunk.to_vec())).collect();
+    ///
+    /// let body = ProgressBody::new(futures::stream::iter(chunks), move |sent| {
+    ///     println!("uploaded {}%", sent * 100 / total);
+    /// });
+    ///
+    /// let result = wit_client.speech(body, AudioType::MP3).await.unwrap();
+    /// # })
+    /// ```
+    pub fn new<S, B, E>(stream: S, mut on_progress: impl FnMut(u64) + Send + Sync + 'static) -> Self
+    where
+        S: Stream<Item = Result<B, E>> + Send + Sync + 'static,
+        B: AsRef<[u8]> + Send + 'static,
+        bytes::Bytes: From<B>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        let mut sent: u64 = 0;
+
+        let stream = stream.inspect_ok(move |chunk| {
+            sent += chunk.as_ref().len() as u64;
+            on_progress(sent);
+        });
+
+        Self {
+            body: Body::wrap_stream(stream),
+        }
+    }
 }
 
-impl ToString for AudioType {
-    fn to_string(&self) -> String {
-        String::from(match self {
-            Self::MP3 => "audio/mpeg",
-            Self::WAV => "audio/wav",
-        })
+impl From<ProgressBody> for Body {
+    fn from(progress_body: ProgressBody) -> Self {
+        progress_body.body
     }
 }
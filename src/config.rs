@@ -0,0 +1,124 @@
+//! Includes a serde-deserializable configuration for creating a `WitClient`, so that its
+//! settings can be read from an application's config file
+
+use crate::{
+    client::{RetryPolicy, WitClient, LATEST_KNOWN_VERSION},
+    errors::Error,
+};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Settings for creating a `WitClient` with `WitClient::from_config`. Every field is
+/// optional, and missing fields keep the client's defaults. Durations are given in
+/// milliseconds
+///
+/// The token is optional so that it does not have to be stored with the rest of the config,
+/// but it must be set (ex. from a secret store) before the client is created
+///
+/// Example, with the config read from JSON:
+/// ```rust
+/// # use wit_ai_rs::config::WitConfig;
+/// let config: WitConfig = serde_json::from_str(r#"{
+///     "version": "20240215",
+///     "timeout_ms": 10000,
+///     "retry": { "max_retries": 3 }
+/// }"#).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct WitConfig {
+    /// The token used to authenticate requests
+    #[serde(default)]
+    pub token: Option<String>,
+    /// The API version, a date string of the form yyyymmdd (ex. 20231231). Defaults to
+    /// `LATEST_KNOWN_VERSION`
+    #[serde(default = "latest_known_version")]
+    pub version: String,
+    /// The API host, see `WitClient::set_api_host`
+    #[serde(default)]
+    pub api_host: Option<String>,
+    /// How long a request may take before it fails, see `WitClient::set_timeout`
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// The `User-Agent` header, see `WitClient::set_user_agent`
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// How failed requests are retried, see `RetryPolicy`
+    #[serde(default)]
+    pub retry: Option<RetryConfig>,
+}
+
+fn latest_known_version() -> String {
+    String::from(LATEST_KNOWN_VERSION)
+}
+
+/// The retry settings of a `WitConfig`, which correspond to the options of `RetryPolicy`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RetryConfig {
+    /// The maximum number of times a failed request is retried
+    pub max_retries: u32,
+    /// How long to wait before the first retry, see `RetryPolicy::initial_backoff`
+    #[serde(default)]
+    pub initial_backoff_ms: Option<u64>,
+    /// How long after a request is first sent it may still be retried, see
+    /// `RetryPolicy::deadline`
+    #[serde(default)]
+    pub deadline_ms: Option<u64>,
+}
+
+impl From<RetryConfig> for RetryPolicy {
+    fn from(config: RetryConfig) -> Self {
+        let mut retry_policy = RetryPolicy::new(config.max_retries);
+
+        if let Some(initial_backoff_ms) = config.initial_backoff_ms {
+            retry_policy = retry_policy.initial_backoff(Duration::from_millis(initial_backoff_ms));
+        }
+
+        if let Some(deadline_ms) = config.deadline_ms {
+            retry_policy = retry_policy.deadline(Duration::from_millis(deadline_ms));
+        }
+
+        retry_policy
+    }
+}
+
+impl WitClient {
+    /// Create a client from a `WitConfig`. Returns an `InvalidArgument` error if the config
+    /// has no token, or any error from `set_api_host` if its API host is invalid
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::{client::WitClient, config::WitConfig};
+    /// let mut config: WitConfig =
+    ///     serde_json::from_str(r#"{ "version": "20240215" }"#).unwrap();
+    ///
+    /// // ex. read from an environment variable or secret store
+    /// config.token = Some(String::from("TOKEN"));
+    ///
+    /// let wit_client = WitClient::from_config(config).unwrap();
+    /// ```
+    pub fn from_config(config: WitConfig) -> Result<Self, Error> {
+        let token = config.token.ok_or_else(|| {
+            Error::InvalidArgument(String::from("the config does not include a token"))
+        })?;
+
+        let mut wit_client = WitClient::new(token, config.version);
+
+        if let Some(api_host) = config.api_host {
+            wit_client = wit_client.set_api_host(api_host)?;
+        }
+
+        if let Some(timeout_ms) = config.timeout_ms {
+            wit_client = wit_client.set_timeout(Duration::from_millis(timeout_ms));
+        }
+
+        if let Some(user_agent) = config.user_agent {
+            wit_client = wit_client.set_user_agent(user_agent);
+        }
+
+        if let Some(retry) = config.retry {
+            wit_client = wit_client.set_retry_policy(retry.into());
+        }
+
+        Ok(wit_client)
+    }
+}
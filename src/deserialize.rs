@@ -0,0 +1,24 @@
+//! Custom deserializers for fields whose JSON type is inconsistent across wit API versions
+
+use serde::{Deserialize, Deserializer};
+
+/// A value that wit may return as either a string or a number
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrNumber {
+    String(String),
+    Number(serde_json::Number),
+}
+
+/// Deserialize a string or a number into a `String`. Used for ids, which wit usually
+/// returns as strings but has been known to return as numbers. Integer ids are kept exactly,
+/// since `serde_json` only falls back to `f64` for integers outside the range of `u64`/`i64`,
+/// which is far beyond any id wit generates
+pub(crate) fn string_or_number<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(value) => value,
+        StringOrNumber::Number(value) => value.to_string(),
+    })
+}
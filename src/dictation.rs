@@ -1,12 +1,18 @@
 //! Includes a method and types related to sending dictation requests to the wit api
 
 use crate::AudioType;
-use crate::{client::WitClient, errors::Error};
+use crate::{
+    client::{self, WitClient},
+    errors::Error,
+};
 use futures::{Stream, StreamExt};
-use reqwest::header::{CONTENT_TYPE, TRANSFER_ENCODING};
+use reqwest::header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT};
 use reqwest::Body;
 use serde::Deserialize;
 use serde_json;
+use std::pin::Pin;
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
 
 /// A token (typically a word) returned from the wit api
 #[derive(Debug, Deserialize)]
@@ -19,6 +25,18 @@ pub struct Token {
     pub end: u64,
     /// The token itself, as a string
     pub token: String,
+    /// Other hypotheses for this token, if wit returned any. Empty otherwise
+    #[serde(default)]
+    pub alternatives: Vec<TokenAlternative>,
+}
+
+/// An alternative hypothesis for a token, which wit considered less likely than the token itself
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct TokenAlternative {
+    /// The alternative token, as a string
+    pub token: String,
+    /// Wit's confidence in this alternative
+    pub confidence: f64,
 }
 
 /// An object containing details about all the tokens in the speech
@@ -42,10 +60,82 @@ pub struct DictationResponse {
     pub is_final: Option<bool>,
 }
 
+/// A boxed stream of dictation results, as returned by `dictation_boxed`. Unlike the `impl Stream`
+/// returned by `dictation`, this type can be named, so it can be stored in a struct field
+pub type DictationStream = Pin<Box<dyn Stream<Item = Result<DictationResponse, Error>> + Send>>;
+
+/// Parse the chunked body of a dictation response into a stream of results, ending the stream
+/// with an error if a single result is larger than `max_object_size`
+fn parse_dictation_response(
+    response: reqwest::Response,
+    max_object_size: Option<usize>,
+) -> impl Stream<Item = Result<DictationResponse, Error>> + Send + 'static {
+    let stream = response.bytes_stream();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut too_large = false;
+
+    let stream_of_streams = stream.map(move |chunk_bytes| {
+        if too_large {
+            return futures::stream::iter(vec![]).right_stream();
+        }
+
+        if let Err(err) = chunk_bytes {
+            return futures::stream::iter(vec![Err(Error::ResponseParseError(err))]).right_stream();
+        }
+
+        let chunk_data =
+            chunk_bytes.expect("chunk_bytes should cause an early return if it is an error");
+
+        buffer.extend_from_slice(&chunk_data);
+
+        let mut dictations = Vec::new();
+        let mut start = 0;
+
+        // every JSON object ends with a carriage return,
+        // except for the last one
+        let json_obj_separator = b"\r\n";
+        let separator_length = json_obj_separator.len();
+
+        while let Some(end) = buffer[start..]
+            .windows(separator_length)
+            .position(|w| w == json_obj_separator)
+        {
+            let json_chunk = &buffer[start..start + end + separator_length];
+            start += end + separator_length;
+
+            if let Ok(json_object) = serde_json::from_slice::<DictationResponse>(json_chunk) {
+                dictations.push(Ok(json_object));
+            }
+        }
+
+        buffer.drain(..start);
+
+        if let Some(max_size) = max_object_size.filter(|max_size| buffer.len() > *max_size) {
+            too_large = true;
+            buffer.clear();
+            dictations.push(Err(Error::ResponseTooLarge(max_size)));
+        }
+
+        // the very last JSON object does not end with a carriage return
+        if buffer.ends_with(b"\n}") {
+            if let Ok(json_object) = serde_json::from_slice::<DictationResponse>(&buffer) {
+                dictations.push(Ok(json_object));
+            }
+        }
+
+        // return the successfully deserialized JSON objects
+        futures::stream::iter(dictations).left_stream()
+    });
+
+    stream_of_streams.flatten()
+}
+
 impl WitClient {
     /// Sends a request to the dictation endpoint of wit, which takes in audio and returns
     /// a stream of partial transcriptions. Here, audio data is the audio data source
     /// (for example, a `tokio::fs::File``), and audio type is the type of audio (ex. mp3 or wav).
+    /// To track upload progress, wrap the audio data in a `ProgressBody`.
     ///
     /// Returns a result of a stream, and each item of this stream is a result where the Ok
     /// variant is a single object, a DictationResponse, representing a partial transcription
@@ -82,70 +172,155 @@ impl WitClient {
         audio_data: impl Into<Body>,
         audio_type: AudioType,
     ) -> Result<impl Stream<Item = Result<DictationResponse, Error>>, Error> {
-        let url = "https://api.wit.ai/dictation?v=20230215";
+        let response = self
+            .send_dictation_request(audio_data, audio_type, None)
+            .await?;
 
-        // internally, when a tokio::fs::File is passed to .body(), it is streamed with ReaderStream
-        // and wrap_stream()
+        Ok(parse_dictation_response(response, self.max_response_size))
+    }
 
-        let stream = self
-            .reqwest_client
-            .post(url)
-            .bearer_auth(&self.auth_token)
-            .header(CONTENT_TYPE, audio_type.to_string())
-            .header(TRANSFER_ENCODING, "chunked") // DO I NEED THIS HEADER?
-            .body(audio_data)
-            .send()
-            .await?
-            .bytes_stream();
-
-        let mut buffer: Vec<u8> = Vec::new();
-
-        let stream_of_streams = stream.map(move |chunk_bytes| {
-            if let Err(err) = chunk_bytes {
-                return futures::stream::iter(vec![Err(Error::ResponseParseError(err))])
-                    .right_stream();
-            }
+    /// Send a request to the dictation endpoint like `dictation`, with the length of the audio
+    /// in bytes given up front. It is sent as the `Content-Length`, so that the audio is
+    /// uploaded in one piece rather than with chunked encoding, which some paths through wit
+    /// handle with less latency. The request fails if the audio turns out to have a different
+    /// length.
+    ///
+    /// Bodies that already know their length (such as a `Vec<u8>`) are sent with a
+    /// `Content-Length` by `dictation`--this is for streamed audio, such as a file. The length
+    /// is not detected from the body, so callers must pass it, for a file by reading it from
+    /// its metadata as below.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    /// let length = file.metadata().await.unwrap().len();
+    ///
+    /// let result = wit_client
+    ///     .dictation_with_length(file, AudioType::MP3, length)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn dictation_with_length(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        content_length: u64,
+    ) -> Result<impl Stream<Item = Result<DictationResponse, Error>>, Error> {
+        let response = self
+            .send_dictation_request(audio_data, audio_type, Some(content_length))
+            .await?;
 
-            let chunk_data =
-                chunk_bytes.expect("chunk_bytes should cause an early return if it is an error");
+        Ok(parse_dictation_response(response, self.max_response_size))
+    }
 
-            buffer.extend_from_slice(&chunk_data);
+    async fn send_dictation_request(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        content_length: Option<u64>,
+    ) -> Result<reqwest::Response, Error> {
+        audio_type.warn_if_not_streamable();
 
-            let mut dictations = Vec::new();
-            let mut start = 0;
+        let url = format!(
+            "{}/dictation?v={}",
+            self.api_host,
+            self.get_streaming_version()
+        );
 
-            // every JSON object ends with a carriage return,
-            // except for the last one
-            let json_obj_separator = b"\r\n";
-            let separator_length = json_obj_separator.len();
+        // internally, when a tokio::fs::File is passed to .body(), it is streamed with ReaderStream
+        // and wrap_stream(). `Transfer-Encoding: chunked` is not set here: hyper already uses it
+        // for bodies of unknown length and sends `Content-Length` for the rest, and setting it by
+        // hand sends both headers for bodies of known length, which some proxies reject
 
-            while let Some(end) = buffer[start..]
-                .windows(separator_length)
-                .position(|w| w == json_obj_separator)
-            {
-                let json_chunk = &buffer[start..start + end + separator_length];
-                start += end + separator_length;
+        let _permit = self.acquire_request_permit().await;
 
-                if let Ok(json_object) = serde_json::from_slice::<DictationResponse>(json_chunk) {
-                    dictations.push(Ok(json_object));
-                }
-            }
+        let mut request = self
+            .reqwest_client
+            .post(url)
+            .bearer_auth(self.auth_token())
+            .header(USER_AGENT, &self.user_agent)
+            .header(CONTENT_TYPE, audio_type.to_string());
 
-            buffer.drain(..start);
+        // hyper sends a streamed body as-is, rather than chunked, when its length is given
+        if let Some(content_length) = content_length {
+            request = request.header(CONTENT_LENGTH, content_length);
+        }
 
-            // the very last JSON object does not end with a carriage return
-            if buffer.ends_with(b"\n}") {
-                if let Ok(json_object) = serde_json::from_slice::<DictationResponse>(&buffer) {
-                    dictations.push(Ok(json_object));
-                }
-            }
+        let response = request.body(audio_data).send().await?;
 
-            // return the successfully deserialized JSON objects
-            futures::stream::iter(dictations).left_stream()
-        });
+        client::log_deprecation_notices(&response);
 
-        let dictations = stream_of_streams.flatten();
+        Ok(response)
+    }
 
-        Ok(dictations)
+    /// Send a request to the dictation endpoint like `dictation`, but return the results as a boxed,
+    /// nameable `DictationStream` rather than an `impl Stream`. This is useful for holding the
+    /// stream in a struct, at the cost of a heap allocation and dynamic dispatch when polling
+    /// the stream--prefer `dictation` when the stream does not need to be named.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::dictation::DictationStream;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// struct Session {
+    ///     results: DictationStream,
+    /// }
+    ///
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    ///
+    /// let session = Session {
+    ///     results: wit_client.dictation_boxed(file, AudioType::MP3).await.unwrap(),
+    /// };
+    /// # })
+    /// ```
+    pub async fn dictation_boxed(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+    ) -> Result<DictationStream, Error> {
+        let response = self
+            .send_dictation_request(audio_data, audio_type, None)
+            .await?;
+
+        Ok(Box::pin(parse_dictation_response(
+            response,
+            self.max_response_size,
+        )))
+    }
+
+    /// Send a request to the dictation endpoint like `dictation`, streaming the audio from anything that
+    /// implements `AsyncRead`, such as `tokio::io::stdin()`. This is useful for command-line tools
+    /// that have audio piped into them.
+    ///
+    /// Example (ex. `cat audio.mp3 | my_tool`):
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use futures::StreamExt;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let result = wit_client
+    ///     .dictation_from_reader(tokio::io::stdin(), AudioType::MP3)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// result.for_each(|res| async move { println!("{:?}", res) }).await;
+    /// # })
+    /// ```
+    pub async fn dictation_from_reader(
+        &self,
+        reader: impl AsyncRead + Send + Sync + 'static,
+        audio_type: AudioType,
+    ) -> Result<impl Stream<Item = Result<DictationResponse, Error>>, Error> {
+        self.dictation(Body::wrap_stream(ReaderStream::new(reader)), audio_type)
+            .await
     }
 }
@@ -0,0 +1,85 @@
+//! Comparing the configuration of two wit apps
+//!
+//! Ids are specific to each app, so intents, entities, and traits are compared by name
+
+use crate::{client::WitClient, errors::Error};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// The differences between two apps' intents, entities, and traits
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AppDiff {
+    /// Differences between the apps' intents
+    pub intents: ResourceDiff,
+    /// Differences between the apps' entities
+    pub entities: ResourceDiff,
+    /// Differences between the apps' traits
+    pub traits: ResourceDiff,
+}
+
+/// The differences between one type of resource (ex. intents) in two apps. Each list of
+/// names is sorted alphabetically
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ResourceDiff {
+    /// Names that exist in the second app but not the first
+    pub added: Vec<String>,
+    /// Names that exist in the first app but not the second
+    pub removed: Vec<String>,
+    /// Names that exist in both apps
+    pub common: Vec<String>,
+}
+
+impl ResourceDiff {
+    fn new(a: BTreeSet<String>, b: BTreeSet<String>) -> Self {
+        Self {
+            added: b.difference(&a).cloned().collect(),
+            removed: a.difference(&b).cloned().collect(),
+            common: a.intersection(&b).cloned().collect(),
+        }
+    }
+}
+
+/// Fetch the names of all intents, entities, and traits of the app associated with `client`
+async fn get_names(
+    client: &WitClient,
+) -> Result<(BTreeSet<String>, BTreeSet<String>, BTreeSet<String>), Error> {
+    let (intents, entities, traits) = futures::try_join!(
+        client.get_intents(),
+        client.get_entities(),
+        client.get_traits()
+    )?;
+
+    Ok((
+        intents.into_iter().map(|intent| intent.name).collect(),
+        entities.into_iter().map(|entity| entity.name).collect(),
+        traits.into_iter().map(|trait_| trait_.name).collect(),
+    ))
+}
+
+/// Compare the intents, entities, and traits of the app associated with `a` to those of the
+/// app associated with `b`. Resources that exist only in `b` are reported as added, and those
+/// that exist only in `a` are reported as removed
+///
+/// Example:
+/// ```rust,no_run
+/// # tokio_test::block_on(async {
+/// # use wit_ai_rs::client::WitClient;
+/// # use wit_ai_rs::diff::{diff_apps, AppDiff};
+/// let prod_client = WitClient::new("PROD_TOKEN", "20240215");
+/// let dev_client = WitClient::new("DEV_TOKEN", "20240215");
+///
+/// let diff: AppDiff = diff_apps(&prod_client, &dev_client).await.unwrap();
+///
+/// println!("entities missing from dev: {:?}", diff.entities.removed);
+/// # })
+/// ```
+pub async fn diff_apps(a: &WitClient, b: &WitClient) -> Result<AppDiff, Error> {
+    let ((a_intents, a_entities, a_traits), (b_intents, b_entities, b_traits)) =
+        futures::try_join!(get_names(a), get_names(b))?;
+
+    Ok(AppDiff {
+        intents: ResourceDiff::new(a_intents, b_intents),
+        entities: ResourceDiff::new(a_entities, b_entities),
+        traits: ResourceDiff::new(a_traits, b_traits),
+    })
+}
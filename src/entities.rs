@@ -4,6 +4,7 @@
 //! managed programmatically
 
 use crate::{client::WitClient, errors::Error, DeleteResponse, EntityBasic, EntityKeyword};
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -11,22 +12,33 @@ use serde_json::Value;
 /// A struct to use for creating a new entity
 #[derive(Debug, Serialize)]
 pub struct NewEntity {
-    name: String,
+    pub(crate) name: String,
     roles: Vec<String>,
     lookups: Option<Vec<String>>,
     keywords: Option<Vec<EntityKeyword>>,
 }
 
+impl NewEntity {
+    /// The JSON body that `create_entity` and `update_entity` send for this entity, for
+    /// checking it against wit's documentation
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("should be able to serialize NewEntity")
+    }
+}
+
 /// Builder for `NewEntity`--use for creating entities
 #[derive(Debug)]
 pub struct NewEntityBuilder {
     new_entity: NewEntity,
+    dedup_synonyms: bool,
 }
 
 impl NewEntityBuilder {
     /// Create a `NewEntityBuilder` with the given name, empty lookups and keywords, and the default role
     /// * `name` - Name for the entity. For built-in entities, use the wit$ prefix.
-    pub fn new(name: String) -> Self {
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+
         Self {
             new_entity: NewEntity {
                 name: name.clone(),
@@ -34,6 +46,45 @@ impl NewEntityBuilder {
                 lookups: None,
                 keywords: None,
             },
+            dedup_synonyms: false,
+        }
+    }
+
+    /// Create a `NewEntityBuilder` with the name, roles, lookups, and keywords of an existing
+    /// entity, so that it can be changed and sent back with `update_entity`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::entities::NewEntityBuilder;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::EntityKeyword;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let existing = wit_client.get_entity("favorite_city").await.unwrap();
+    ///
+    /// let mut keywords = existing.keywords.clone().unwrap_or_default();
+    /// keywords.push(EntityKeyword::new("Paris", vec!["Paris".to_string()]));
+    ///
+    /// let updated = NewEntityBuilder::from_response(&existing)
+    ///     .keywords(keywords)
+    ///     .build();
+    ///
+    /// wit_client.update_entity("favorite_city", updated).await.unwrap();
+    /// # })
+    /// ```
+    pub fn from_response(response: &EntityResponse) -> Self {
+        Self {
+            new_entity: NewEntity {
+                name: response.name.clone(),
+                roles: response
+                    .roles
+                    .iter()
+                    .map(|role| role.name.clone())
+                    .collect(),
+                lookups: response.lookups.clone(),
+                keywords: response.keywords.clone(),
+            },
+            dedup_synonyms: false,
         }
     }
 
@@ -56,16 +107,46 @@ impl NewEntityBuilder {
         self
     }
 
+    /// Remove duplicate synonyms from each keyword when building (see
+    /// `EntityKeyword::dedup_synonyms`). Wit removes them itself, so this keeps the keywords
+    /// sent identical to the ones wit returns afterwards. Off by default
+    pub fn dedup_synonyms(mut self, dedup_synonyms: bool) -> Self {
+        self.dedup_synonyms = dedup_synonyms;
+        self
+    }
+
     /// Create a `NewEntity` from this `NewEntityBuilder`
-    pub fn build(self) -> NewEntity {
+    pub fn build(mut self) -> NewEntity {
+        if let Some(keywords) = self
+            .new_entity
+            .keywords
+            .as_mut()
+            .filter(|_| self.dedup_synonyms)
+        {
+            keywords.iter_mut().for_each(EntityKeyword::dedup_synonyms);
+        }
+
         self.new_entity
     }
 }
 
+impl From<EntityResponse> for NewEntity {
+    /// The same as `NewEntityBuilder::from_response`, without any changes
+    fn from(response: EntityResponse) -> Self {
+        NewEntity {
+            name: response.name,
+            roles: response.roles.into_iter().map(|role| role.name).collect(),
+            lookups: response.lookups,
+            keywords: response.keywords,
+        }
+    }
+}
+
 /// A response from creating, updating, or getting an entity
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct EntityResponse {
     /// The id of the entity
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the entity
     pub name: String,
@@ -77,10 +158,123 @@ pub struct EntityResponse {
     pub keywords: Option<Vec<EntityKeyword>>,
 }
 
+impl EntityResponse {
+    /// Whether this entity has a keyword with the given canonical value. Always false for
+    /// entities without keywords (ex. built-in entities)
+    pub fn has_keyword(&self, keyword: &str) -> bool {
+        self.keywords
+            .as_ref()
+            .is_some_and(|keywords| keywords.iter().any(|existing| existing.keyword == keyword))
+    }
+
+    /// A hash of this entity's definition (its name, role names, lookups, keywords, and
+    /// synonyms) that can be stored and compared with a later fetch to detect changes.
+    ///
+    /// Roles, lookups, keywords, and synonyms are sorted before hashing, since wit may
+    /// return them in a different order. The id is not included, so the same definition
+    /// has the same signature across apps. The hash is stable across runs, platforms, and
+    /// crate versions, unlike the hashers in the standard library
+    pub fn signature(&self) -> u64 {
+        let mut roles: Vec<&str> = self.roles.iter().map(|role| role.name.as_str()).collect();
+        roles.sort_unstable();
+
+        let lookups = self.lookups.as_ref().map(|lookups| {
+            let mut lookups: Vec<&str> = lookups.iter().map(String::as_str).collect();
+            lookups.sort_unstable();
+            lookups
+        });
+
+        let keywords = self.keywords.as_ref().map(|keywords| {
+            let mut keywords: Vec<(&str, Vec<&str>)> = keywords
+                .iter()
+                .map(|keyword| {
+                    let mut synonyms: Vec<&str> =
+                        keyword.synonyms.iter().map(String::as_str).collect();
+                    synonyms.sort_unstable();
+                    (keyword.keyword.as_str(), synonyms)
+                })
+                .collect();
+            keywords.sort_unstable();
+            keywords
+        });
+
+        let mut hasher = SignatureHasher::new();
+
+        hasher.write_str(&self.name);
+        hasher.write_strs(&roles);
+
+        hasher.write_option(lookups.as_deref(), |hasher, lookups| {
+            hasher.write_strs(lookups)
+        });
+
+        hasher.write_option(keywords.as_deref(), |hasher, keywords| {
+            hasher.write_len(keywords.len());
+            for (keyword, synonyms) in keywords {
+                hasher.write_str(keyword);
+                hasher.write_strs(synonyms);
+            }
+        });
+
+        hasher.finish()
+    }
+}
+
+/// A 64-bit FNV-1a hasher, used for `EntityResponse::signature` because its output
+/// does not depend on the platform or Rust version. Every variable-length value is
+/// prefixed with its length so that different field splits cannot collide
+struct SignatureHasher(u64);
+
+impl SignatureHasher {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_len(&mut self, len: usize) {
+        self.write(&(len as u64).to_le_bytes());
+    }
+
+    fn write_str(&mut self, value: &str) {
+        self.write_len(value.len());
+        self.write(value.as_bytes());
+    }
+
+    fn write_strs(&mut self, values: &[&str]) {
+        self.write_len(values.len());
+        for value in values {
+            self.write_str(value);
+        }
+    }
+
+    fn write_option<T: ?Sized>(&mut self, value: Option<&T>, write: impl FnOnce(&mut Self, &T)) {
+        match value {
+            Some(value) => {
+                self.write(&[1]);
+                write(self, value);
+            }
+            None => self.write(&[0]),
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
 /// A role for an entity
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct EntityRole {
     /// The id of the role
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the role
     pub name: String,
@@ -89,6 +283,10 @@ pub struct EntityRole {
 impl WitClient {
     /// Returns basic information about all entities
     ///
+    /// Wit does not support paging on this endpoint (unlike `get_utterances`), so every
+    /// entity is returned in one response. Only names and ids are included, so the response
+    /// stays small even for apps with hundreds of entities--use `get_entity` for details.
+    ///
     /// Example:
     /// ```rust,no_run
     /// # tokio_test::block_on(async {
@@ -103,6 +301,84 @@ impl WitClient {
             .await
     }
 
+    /// Returns detailed information about all entities. Wit has no endpoint for this, so
+    /// it lists the entities and then fetches each one, with at most `concurrency` requests
+    /// in flight at once. The entities are returned in the same order as `get_entities`
+    /// returns them.
+    ///
+    /// Returns an `InvalidArgument` error if `concurrency` is 0. If fetching an entity fails,
+    /// `Error::EntityError` is returned with the entity's name and the error
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::entities::EntityResponse;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: Vec<EntityResponse> = wit_client.get_entities_detailed(4).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn get_entities_detailed(
+        &self,
+        concurrency: usize,
+    ) -> Result<Vec<EntityResponse>, Error> {
+        if concurrency == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "concurrency must be greater than 0",
+            )));
+        }
+
+        let entities = self.get_entities().await?;
+
+        futures::stream::iter(entities)
+            .map(|entity| async move {
+                self.get_entity(&entity.name)
+                    .await
+                    .map_err(|err| Error::EntityError(entity.name, Box::new(err)))
+            })
+            .buffered(concurrency)
+            .try_collect()
+            .await
+    }
+
+    /// Returns every role defined in the app, each paired with the name of the entity it
+    /// belongs to. Useful for checking that the `entity:role` names used in utterance
+    /// annotations exist. The entities are fetched as in `get_entities_detailed`, with at
+    /// most `concurrency` requests in flight at once
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let roles = wit_client.get_all_roles(4).await.unwrap();
+    ///
+    /// for (entity_name, role) in roles {
+    ///     println!("{entity_name}:{}", role.name);
+    /// }
+    /// # })
+    /// ```
+    pub async fn get_all_roles(
+        &self,
+        concurrency: usize,
+    ) -> Result<Vec<(String, EntityRole)>, Error> {
+        let roles = self
+            .get_entities_detailed(concurrency)
+            .await?
+            .into_iter()
+            .flat_map(|entity| {
+                let name = entity.name;
+
+                entity
+                    .roles
+                    .into_iter()
+                    .map(move |role| (name.clone(), role))
+            })
+            .collect();
+
+        Ok(roles)
+    }
+
     /// Creates a new entity
     ///
     /// Example:
@@ -111,7 +387,7 @@ impl WitClient {
     /// # use wit_ai_rs::entities::{EntityResponse, NewEntityBuilder};
     /// # use wit_ai_rs::client::WitClient;
     /// # let wit_client = WitClient::new(String::new(), String::new());
-    /// let new_entity = NewEntityBuilder::new("entity_name".to_string())
+    /// let new_entity = NewEntityBuilder::new("entity_name")
     ///     .roles(vec!["role".to_string()])
     ///     .build();
     ///
@@ -123,6 +399,34 @@ impl WitClient {
             .await
     }
 
+    /// Creates a new entity if no entity with the same name exists, otherwise returns the
+    /// existing entity unchanged. Unlike `create_entity`, this can safely be called repeatedly
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::entities::{EntityResponse, NewEntityBuilder};
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let new_entity = NewEntityBuilder::new("entity_name").build();
+    ///
+    /// let response: EntityResponse = wit_client.ensure_entity(new_entity).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn ensure_entity(&self, new_entity: NewEntity) -> Result<EntityResponse, Error> {
+        let exists = self
+            .get_entities()
+            .await?
+            .iter()
+            .any(|entity| entity.name == new_entity.name);
+
+        if exists {
+            return self.get_entity(new_entity.name).await;
+        }
+
+        self.create_entity(new_entity).await
+    }
+
     /// Returns information about the entity with the given name
     ///
     /// Example:
@@ -131,14 +435,44 @@ impl WitClient {
     /// # use wit_ai_rs::entities::EntityResponse;
     /// # use wit_ai_rs::client::WitClient;
     /// # let wit_client = WitClient::new(String::new(), String::new());
-    /// let response: EntityResponse = wit_client.get_entity("entity".to_string()).await.unwrap();
+    /// let response: EntityResponse = wit_client.get_entity("entity").await.unwrap();
     /// # })
     /// ```
-    pub async fn get_entity(&self, entity_name: String) -> Result<EntityResponse, Error> {
+    pub async fn get_entity(&self, entity_name: impl AsRef<str>) -> Result<EntityResponse, Error> {
+        let entity_name = entity_name.as_ref();
         let endpoint = format!("/entities/{}", entity_name);
 
         self.make_request(Method::GET, &endpoint, vec![], Option::<Value>::None)
             .await
+            .map_err(|err| {
+                err.map_not_found(|source| Error::EntityNotFound(entity_name.to_owned(), source))
+            })
+    }
+
+    /// Returns whether the entity with the given name has a keyword with the given canonical
+    /// value. Wit has no server-side keyword search, so this fetches the whole entity--to check
+    /// many keywords against a large entity, fetch it once with `get_entity` and use
+    /// `EntityResponse::has_keyword` instead.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let exists: bool = wit_client
+    ///     .entity_has_keyword("favorite_city", "Paris")
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn entity_has_keyword(
+        &self,
+        entity_name: impl AsRef<str>,
+        keyword: &str,
+    ) -> Result<bool, Error> {
+        let entity = self.get_entity(entity_name).await?;
+
+        Ok(entity.has_keyword(keyword))
     }
 
     /// Update information about an entity with the current name `old_name`, overwriting its
@@ -150,7 +484,7 @@ impl WitClient {
     /// # use wit_ai_rs::entities::{EntityResponse, NewEntityBuilder};
     /// # use wit_ai_rs::client::WitClient;
     /// # let wit_client = WitClient::new(String::new(), String::new());
-    /// let updated_entity = NewEntityBuilder::new("updated_name".to_string())
+    /// let updated_entity = NewEntityBuilder::new("updated_name")
     ///     .roles(vec!["updated_role".to_string()])
     ///     .build();
     ///
@@ -160,15 +494,61 @@ impl WitClient {
     /// ```
     pub async fn update_entity(
         &self,
-        old_name: &str,
+        old_name: impl AsRef<str>,
         updated_entity: NewEntity,
     ) -> Result<EntityResponse, Error> {
-        let endpoint = format!("/entities/{}", old_name);
+        let endpoint = format!("/entities/{}", old_name.as_ref());
 
         self.make_request(Method::PUT, &endpoint, vec![], Some(updated_entity))
             .await
     }
 
+    /// Update an entity like `update_entity`, but only if it has not changed since it was last
+    /// seen as `expected`. If the entity currently stored by wit differs from `expected`,
+    /// `Error::Conflict` is returned and no update is sent.
+    ///
+    /// Wit does not support ETags or any other versioning for entities, so this fetches the
+    /// entity and compares it before writing. This detects changes made (for example, in the web
+    /// console) between when `expected` was fetched and when this method is called, but the check
+    /// and the write are not atomic: a change made in the short window between them will still
+    /// be overwritten.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::entities::{EntityResponse, NewEntityBuilder};
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let seen: EntityResponse = wit_client.get_entity("entity_name").await.unwrap();
+    ///
+    /// let updated_entity = NewEntityBuilder::new("entity_name")
+    ///     .roles(vec!["updated_role".to_string()])
+    ///     .build();
+    ///
+    /// let response: EntityResponse = wit_client
+    ///     .update_entity_if_unchanged("entity_name", &seen, updated_entity)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn update_entity_if_unchanged(
+        &self,
+        old_name: impl AsRef<str>,
+        expected: &EntityResponse,
+        updated_entity: NewEntity,
+    ) -> Result<EntityResponse, Error> {
+        let old_name = old_name.as_ref();
+        let current = self.get_entity(old_name).await?;
+
+        if &current != expected {
+            return Err(Error::Conflict(format!(
+                "entity {old_name} has changed since it was last fetched"
+            )));
+        }
+
+        self.update_entity(old_name, updated_entity).await
+    }
+
     /// Deletes the entity with the given name
     ///
     /// Example:
@@ -180,10 +560,65 @@ impl WitClient {
     /// let response: DeleteResponse = wit_client.delete_entity("entity_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn delete_entity(&self, entity_name: &str) -> Result<DeleteResponse, Error> {
-        let endpoint = format!("/entities/{}", entity_name);
+    pub async fn delete_entity(
+        &self,
+        entity_name: impl AsRef<str>,
+    ) -> Result<DeleteResponse, Error> {
+        let endpoint = format!("/entities/{}", entity_name.as_ref());
 
         self.make_request(Method::DELETE, &endpoint, vec![], Option::<Value>::None)
             .await
     }
+
+    /// Enables a built-in entity (ex. `wit$datetime`) on the app, which is required before wit
+    /// resolves it in messages. The entity is created with a single role named after it
+    /// without the prefix (ex. `datetime`), as wit does when a built-in is enabled from the
+    /// dashboard. Returns an `InvalidArgument` error if `name` does not start with `wit$`
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::entities::EntityResponse;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: EntityResponse = wit_client.enable_builtin("wit$datetime").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn enable_builtin(&self, name: &str) -> Result<EntityResponse, Error> {
+        let role = builtin_role(name)?;
+
+        let new_entity = NewEntityBuilder::new(name)
+            .roles(vec![role.to_owned()])
+            .build();
+
+        self.create_entity(new_entity).await
+    }
+
+    /// Disables a built-in entity on the app by deleting it. Returns an `InvalidArgument` error
+    /// if `name` does not start with `wit$`, so that a custom entity is never deleted by mistake
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::DeleteResponse;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: DeleteResponse = wit_client.disable_builtin("wit$datetime").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn disable_builtin(&self, name: &str) -> Result<DeleteResponse, Error> {
+        builtin_role(name)?;
+
+        self.delete_entity(name).await
+    }
+}
+
+/// The name of a built-in entity without its `wit$` prefix, which is also its default role
+fn builtin_role(name: &str) -> Result<&str, Error> {
+    match name.strip_prefix("wit$") {
+        Some(role) if !role.is_empty() => Ok(role),
+        _ => Err(Error::InvalidArgument(format!(
+            "`{name}` is not a built-in entity name, which starts with `wit$` (ex. `wit$datetime`)"
+        ))),
+    }
 }
@@ -1,5 +1,7 @@
 //! wit_ai_rs crate-related errors
 
+use crate::provision::ProvisionStep;
+use reqwest::StatusCode;
 use serde::Deserialize;
 
 /// Errors that may occur while using the wit_ai_rs crate
@@ -13,15 +15,48 @@ pub enum Error {
     InvalidArgument(String),
     /// The request was sent and the response parsed successfully, but wit returned an error
     WitError(ErrorResponse),
+    /// Wit rejected the request's token (HTTP 401), either because it is invalid or because
+    /// it has been revoked
+    AuthError(ErrorResponse),
     /// An error parsing the url (base string + headers)
     URLParseError(url::ParseError),
     /// An error that may occur while parsing JSON
     JSONParseError(String),
+    /// The entity with the given name does not exist in the app (wit returned a 404 with the
+    /// given error for the entity's endpoint)
+    EntityNotFound(String, ErrorResponse),
+    /// The intent with the given name does not exist in the app (wit returned a 404 with the
+    /// given error for the intent's endpoint)
+    IntentNotFound(String, ErrorResponse),
+    /// The trait with the given name does not exist in the app (wit returned a 404 with the
+    /// given error for the trait's endpoint)
+    TraitNotFound(String, ErrorResponse),
+    /// Wit could not find the app that the token belongs to (it returned a 404 with the given
+    /// error for the app's endpoint)
+    AppNotFound(ErrorResponse),
+    /// Wit listed no apps for the token, so there is no app for it to belong to
+    NoAppForToken,
+    /// The response was larger than the limit in bytes set with
+    /// `WitClient::set_max_response_size`, so it was not read
+    ResponseTooLarge(usize),
+    /// A conditional update was not sent because the resource changed since it was last seen
+    Conflict(String),
+    /// Provisioning an app failed at the given step, with the given error
+    ProvisionError(ProvisionStep, Box<Error>),
+    /// An error reading input, such as a file of utterances to import
+    IoError(std::io::Error),
+    /// Fetching the intent with the given name failed with the given error, while fetching
+    /// several intents at once
+    IntentError(String, Box<Error>),
+    /// Fetching the entity with the given name failed with the given error, while fetching
+    /// several entities at once
+    EntityError(String, Box<Error>),
 }
 
 impl From<reqwest::Error> for Error {
     fn from(error: reqwest::Error) -> Self {
-        if error.is_body() || error.is_decode() {
+        // a timeout while reading the body is still a timeout, not a problem with the body
+        if (error.is_body() || error.is_decode()) && !error.is_timeout() {
             Self::ResponseParseError(error)
         } else {
             Self::RequestError(error)
@@ -31,7 +66,11 @@ impl From<reqwest::Error> for Error {
 
 impl From<ErrorResponse> for Error {
     fn from(error_json: ErrorResponse) -> Self {
-        Self::WitError(error_json)
+        if error_json.status == Some(StatusCode::UNAUTHORIZED) {
+            Self::AuthError(error_json)
+        } else {
+            Self::WitError(error_json)
+        }
     }
 }
 
@@ -41,6 +80,12 @@ impl From<url::ParseError> for Error {
     }
 }
 
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Self::IoError(error)
+    }
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -48,8 +93,32 @@ impl std::fmt::Display for Error {
             Self::ResponseParseError(source) => write!(f, "response parse error: {}", source),
             Self::InvalidArgument(details) => write!(f, "invalid argument: {}", details),
             Self::WitError(source) => write!(f, "error from wit.ai: {}", source),
+            Self::AuthError(source) => write!(f, "authentication error from wit.ai: {}", source),
             Self::URLParseError(source) => write!(f, "URL parse error: {}", source),
             Self::JSONParseError(details) => write!(f, "JSON parse error: {}", details),
+            Self::EntityNotFound(name, _) => write!(f, "entity not found: {}", name),
+            Self::IntentNotFound(name, _) => write!(f, "intent not found: {}", name),
+            Self::TraitNotFound(name, _) => write!(f, "trait not found: {}", name),
+            Self::AppNotFound(source) => write!(f, "app not found: {}", source),
+            Self::NoAppForToken => write!(f, "wit listed no apps for the token"),
+            Self::ResponseTooLarge(max_size) => {
+                write!(
+                    f,
+                    "response exceeded the maximum size of {} bytes",
+                    max_size
+                )
+            }
+            Self::Conflict(details) => write!(f, "conflict: {}", details),
+            Self::ProvisionError(step, source) => {
+                write!(f, "provisioning failed at {}: {}", step, source)
+            }
+            Self::IoError(source) => write!(f, "I/O error: {}", source),
+            Self::IntentError(name, source) => {
+                write!(f, "fetching intent {} failed: {}", name, source)
+            }
+            Self::EntityError(name, source) => {
+                write!(f, "fetching entity {} failed: {}", name, source)
+            }
         }
     }
 }
@@ -61,8 +130,105 @@ impl std::error::Error for Error {
             Self::ResponseParseError(source) => Some(source),
             Self::InvalidArgument(_) => None,
             Self::WitError(source) => Some(source),
+            Self::AuthError(source) => Some(source),
             Self::URLParseError(source) => Some(source),
             Self::JSONParseError(_) => None,
+            Self::EntityNotFound(_, source)
+            | Self::IntentNotFound(_, source)
+            | Self::TraitNotFound(_, source)
+            | Self::AppNotFound(source) => Some(source),
+            Self::NoAppForToken => None,
+            Self::ResponseTooLarge(_) => None,
+            Self::Conflict(_) => None,
+            Self::ProvisionError(_, source) => Some(source.as_ref()),
+            Self::IoError(source) => Some(source),
+            Self::IntentError(_, source) => Some(source.as_ref()),
+            Self::EntityError(_, source) => Some(source.as_ref()),
+        }
+    }
+}
+
+impl Error {
+    /// Whether the request that caused this error might succeed if retried: true for timeouts,
+    /// connection errors, and rate limiting (429) or server (5xx) errors from wit, and false for
+    /// invalid arguments, other errors from wit, and parse errors
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RequestError(source) => source.is_timeout() || source.is_connect(),
+            Self::WitError(source) => source.status.is_some_and(|status| {
+                status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }),
+            Self::ProvisionError(_, source)
+            | Self::IntentError(_, source)
+            | Self::EntityError(_, source) => source.is_retryable(),
+            Self::ResponseParseError(_)
+            | Self::AuthError(_)
+            | Self::InvalidArgument(_)
+            | Self::URLParseError(_)
+            | Self::JSONParseError(_)
+            | Self::EntityNotFound(..)
+            | Self::IntentNotFound(..)
+            | Self::TraitNotFound(..)
+            | Self::AppNotFound(_)
+            | Self::NoAppForToken
+            | Self::ResponseTooLarge(_)
+            | Self::IoError(_)
+            | Self::Conflict(_) => false,
+        }
+    }
+
+    /// Whether this error happened while connecting to wit, for example because the
+    /// connection was refused or the TLS handshake failed. DNS failures also count as
+    /// connection errors, so check `is_dns` first to tell them apart
+    pub fn is_connect(&self) -> bool {
+        self.request_error()
+            .is_some_and(|source| source.is_connect())
+    }
+
+    /// Whether the request timed out
+    pub fn is_timeout(&self) -> bool {
+        self.request_error()
+            .is_some_and(|source| source.is_timeout())
+    }
+
+    /// Whether the API host could not be resolved. reqwest does not classify DNS errors
+    /// itself, so this looks for the resolver's error among the causes of a connection error
+    pub fn is_dns(&self) -> bool {
+        self.request_error().is_some_and(|source| {
+            let mut cause: Option<&(dyn std::error::Error + 'static)> = Some(source);
+
+            source.is_connect()
+                && std::iter::from_fn(|| {
+                    let current = cause?;
+                    cause = current.source();
+                    Some(current)
+                })
+                .any(|cause| cause.to_string().starts_with("dns error"))
+        })
+    }
+
+    /// The reqwest error behind this error, looking through errors that wrap another error
+    /// (`ProvisionError`, `IntentError`, `EntityError`)
+    fn request_error(&self) -> Option<&reqwest::Error> {
+        match self {
+            Self::RequestError(source) => Some(source),
+            Self::ProvisionError(_, source)
+            | Self::IntentError(_, source)
+            | Self::EntityError(_, source) => source.request_error(),
+            _ => None,
+        }
+    }
+
+    /// Replace a 404 error from wit with `not_found`, given wit's error. Wit uses a 404 both
+    /// for a missing resource and for a missing app, with no reliable way to tell them apart
+    /// from the error itself, so the caller decides from the endpoint it called: a resource's
+    /// endpoint maps it to that resource's error, and an app's endpoint to `AppNotFound`
+    pub(crate) fn map_not_found(self, not_found: impl FnOnce(ErrorResponse) -> Error) -> Error {
+        match self {
+            Self::WitError(source) if source.status == Some(StatusCode::NOT_FOUND) => {
+                not_found(source)
+            }
+            other => other,
         }
     }
 }
@@ -74,6 +240,9 @@ pub struct ErrorResponse {
     pub error: String,
     /// The error type (not a numeric value)
     pub code: String,
+    /// The HTTP status of the response containing the error
+    #[serde(skip)]
+    pub status: Option<StatusCode>,
 }
 
 impl std::fmt::Display for ErrorResponse {
@@ -0,0 +1,174 @@
+//! Evaluating how well an app understands a set of test queries, by comparing the intent wit
+//! predicts for each query to the expected intent
+
+use crate::{
+    client::WitClient,
+    errors::Error,
+    message::{MessageOptions, MessageResponse},
+    Confidence,
+};
+use futures::{StreamExt, TryStreamExt};
+use std::collections::BTreeMap;
+
+/// The result of evaluating one test query
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    /// The query that was sent
+    pub query: String,
+    /// The intent the query was expected to have
+    pub expected_intent: String,
+    /// The intent wit was most confident in, if any
+    pub predicted_intent: Option<String>,
+    /// Wit's confidence in the predicted intent
+    pub confidence: Option<Confidence>,
+}
+
+impl CaseResult {
+    /// Whether wit predicted the expected intent
+    pub fn is_correct(&self) -> bool {
+        self.predicted_intent.as_deref() == Some(self.expected_intent.as_str())
+    }
+}
+
+/// A pair of expected and predicted intents that wit confused, and how often it did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfusionPair {
+    /// The intent the queries were expected to have
+    pub expected: String,
+    /// The intent wit predicted instead, or `None` if it predicted no intent
+    pub predicted: Option<String>,
+    /// The number of queries with this pair of intents
+    pub count: usize,
+}
+
+/// The results of `WitClient::evaluate`, one per test case in the order the cases were given
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalReport {
+    /// The result of each test case
+    pub results: Vec<CaseResult>,
+}
+
+impl EvalReport {
+    /// The fraction of cases where wit predicted the expected intent, from 0.0 to 1.0. This
+    /// is 0.0 when there are no cases
+    pub fn accuracy(&self) -> f64 {
+        if self.results.is_empty() {
+            return 0.0;
+        }
+
+        let correct = self
+            .results
+            .iter()
+            .filter(|result| result.is_correct())
+            .count();
+
+        correct as f64 / self.results.len() as f64
+    }
+
+    /// Every pair of expected and predicted intents that did not match, most frequent first
+    /// (and then alphabetically)
+    pub fn confusion_pairs(&self) -> Vec<ConfusionPair> {
+        let mut counts: BTreeMap<(&str, Option<&str>), usize> = BTreeMap::new();
+
+        for result in self.results.iter().filter(|result| !result.is_correct()) {
+            let pair = (
+                result.expected_intent.as_str(),
+                result.predicted_intent.as_deref(),
+            );
+
+            *counts.entry(pair).or_default() += 1;
+        }
+
+        let mut pairs: Vec<ConfusionPair> = counts
+            .into_iter()
+            .map(|((expected, predicted), count)| ConfusionPair {
+                expected: expected.to_owned(),
+                predicted: predicted.map(str::to_owned),
+                count,
+            })
+            .collect();
+
+        // the sort is stable, so pairs with the same count stay in alphabetical order
+        pairs.sort_by_key(|pair| std::cmp::Reverse(pair.count));
+
+        pairs
+    }
+
+    /// The cases where wit predicted no intent, or predicted one with a confidence below
+    /// `threshold`, whether or not the prediction was correct
+    pub fn low_confidence(&self, threshold: f64) -> impl Iterator<Item = &CaseResult> {
+        self.results.iter().filter(move |result| {
+            result
+                .confidence
+                .is_none_or(|confidence| confidence.into_inner() < threshold)
+        })
+    }
+}
+
+impl WitClient {
+    /// Send each test query to the message endpoint with the given options, and compare the
+    /// intent wit is most confident in to the expected intent. Each case is a
+    /// `(query, expected_intent)` pair. At most `concurrency` queries are sent at once, and the
+    /// first error returned fails the whole evaluation.
+    ///
+    /// Returns an `InvalidArgument` error if `concurrency` is 0
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::evaluation::EvalReport;
+    /// # use wit_ai_rs::message::MessageOptions;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let cases = vec![
+    ///     (String::from("book a flight to Paris"), String::from("book_flight")),
+    ///     (String::from("what's the weather"), String::from("get_weather")),
+    /// ];
+    ///
+    /// let report: EvalReport = wit_client
+    ///     .evaluate(cases, MessageOptions::default(), 4)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// println!("accuracy: {:.1}%", report.accuracy() * 100.0);
+    ///
+    /// for pair in report.confusion_pairs() {
+    ///     println!("{} -> {:?} ({} times)", pair.expected, pair.predicted, pair.count);
+    /// }
+    /// # })
+    /// ```
+    pub async fn evaluate(
+        &self,
+        cases: Vec<(String, String)>,
+        options: MessageOptions,
+        concurrency: usize,
+    ) -> Result<EvalReport, Error> {
+        if concurrency == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "concurrency must be greater than 0",
+            )));
+        }
+
+        let results = futures::stream::iter(cases)
+            .map(|(query, expected_intent)| {
+                let options = options.clone();
+
+                async move {
+                    let response: MessageResponse = self.message(query.clone(), options).await?;
+                    let top_intent = response.top_intent();
+
+                    Ok::<_, Error>(CaseResult {
+                        query,
+                        expected_intent,
+                        predicted_intent: top_intent.map(|intent| intent.name.clone()),
+                        confidence: top_intent.map(|intent| intent.confidence),
+                    })
+                }
+            })
+            .buffered(concurrency)
+            .try_collect()
+            .await?;
+
+        Ok(EvalReport { results })
+    }
+}
@@ -5,6 +5,7 @@ use crate::{
     common_types::{DeleteResponse, EntityBasic, IntentBasic},
     errors::Error,
 };
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Method;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -13,6 +14,7 @@ use serde_json::{json, Value};
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct IntentResponse {
     /// The id of the intent
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the intent
     pub name: String,
@@ -23,6 +25,9 @@ pub struct IntentResponse {
 impl WitClient {
     /// Get basic information about all intents associated with an app
     ///
+    /// All intents are returned at once, since wit has no `limit` or `offset` parameters for
+    /// this endpoint. Each intent is just a name and id; `get_intent` returns the rest.
+    ///
     /// Example:
     /// ```rust,no_run
     /// # tokio_test::block_on(async {
@@ -48,13 +53,39 @@ impl WitClient {
     /// let response: IntentBasic = wit_client.create_intent("intent_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn create_intent(&self, intent_name: &str) -> Result<IntentBasic, Error> {
-        let new_intent = json!({"name": intent_name});
+    pub async fn create_intent(&self, intent_name: impl AsRef<str>) -> Result<IntentBasic, Error> {
+        let new_intent = json!({"name": intent_name.as_ref()});
 
         self.make_request(Method::POST, "/intents", vec![], Some(new_intent))
             .await
     }
 
+    /// Create a new intent if no intent with the same name exists, otherwise return the
+    /// existing intent. Unlike `create_intent`, this can safely be called repeatedly
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::IntentBasic;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: IntentBasic = wit_client.ensure_intent("intent_name").await.unwrap();
+    /// # })
+    /// ```
+    pub async fn ensure_intent(&self, intent_name: impl AsRef<str>) -> Result<IntentBasic, Error> {
+        let intent_name = intent_name.as_ref();
+        let existing = self
+            .get_intents()
+            .await?
+            .into_iter()
+            .find(|intent| intent.name == intent_name);
+
+        match existing {
+            Some(intent) => Ok(intent),
+            None => self.create_intent(intent_name).await,
+        }
+    }
+
     /// Get more detailed information about a specific intent
     ///
     /// Example:
@@ -66,11 +97,55 @@ impl WitClient {
     /// let response: IntentResponse = wit_client.get_intent("intent_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn get_intent(&self, intent_name: &str) -> Result<IntentResponse, Error> {
+    pub async fn get_intent(&self, intent_name: impl AsRef<str>) -> Result<IntentResponse, Error> {
+        let intent_name = intent_name.as_ref();
         let endpoint = format!("/intents/{}", intent_name);
 
         self.make_request(Method::GET, &endpoint, vec![], Option::<Value>::None)
             .await
+            .map_err(|err| {
+                err.map_not_found(|source| Error::IntentNotFound(intent_name.to_owned(), source))
+            })
+    }
+
+    /// Get detailed information, including associated entities, about every intent. Wit has
+    /// no endpoint for this, so it lists the intents and then fetches each one, with at most
+    /// `concurrency` requests in flight at once. The intents are returned in the same order as
+    /// `get_intents` returns them.
+    ///
+    /// Returns an `InvalidArgument` error if `concurrency` is 0. If fetching an intent fails,
+    /// `Error::IntentError` is returned with the intent's name and the error
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::intents::IntentResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: Vec<IntentResponse> = wit_client.get_intents_detailed(4).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn get_intents_detailed(
+        &self,
+        concurrency: usize,
+    ) -> Result<Vec<IntentResponse>, Error> {
+        if concurrency == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "concurrency must be greater than 0",
+            )));
+        }
+
+        let intents = self.get_intents().await?;
+
+        futures::stream::iter(intents)
+            .map(|intent| async move {
+                self.get_intent(&intent.name)
+                    .await
+                    .map_err(|err| Error::IntentError(intent.name, Box::new(err)))
+            })
+            .buffered(concurrency)
+            .try_collect()
+            .await
     }
 
     /// Delete an intent by name
@@ -84,8 +159,11 @@ impl WitClient {
     /// let response: DeleteResponse = wit_client.delete_intent("intent_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn delete_intent(&self, intent_name: &str) -> Result<DeleteResponse, Error> {
-        let endpoint = format!("/intents/{}", intent_name);
+    pub async fn delete_intent(
+        &self,
+        intent_name: impl AsRef<str>,
+    ) -> Result<DeleteResponse, Error> {
+        let endpoint = format!("/intents/{}", intent_name.as_ref());
 
         self.make_request(Method::DELETE, &endpoint, vec![], Option::<Value>::None)
             .await
@@ -0,0 +1,162 @@
+//! Incremental deserialization of JSON arrays from a stream of bytes, so that large list
+//! responses can be processed one element at a time without buffering the whole body
+
+use crate::errors::Error;
+use bytes::Bytes;
+use futures::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+
+/// Splits a JSON array into the raw bytes of its elements as chunks of it arrive. Only the
+/// bytes of the element currently being read are kept in memory
+#[derive(Debug, Default)]
+struct ArraySplitter {
+    max_element_size: Option<usize>,
+    element: Vec<u8>,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    started: bool,
+    complete: bool,
+}
+
+impl ArraySplitter {
+    /// Read the next chunk of the array, returning every element that was completed by it
+    fn push<T: DeserializeOwned>(&mut self, chunk: &[u8]) -> Vec<Result<T, Error>> {
+        let mut elements = Vec::new();
+
+        for &byte in chunk {
+            if self.complete {
+                break;
+            }
+
+            if !self.started {
+                match byte {
+                    b'[' => {
+                        self.started = true;
+                        self.depth = 1;
+                    }
+                    byte if byte.is_ascii_whitespace() => {}
+                    _ => {
+                        self.complete = true;
+                        elements.push(Err(Error::JSONParseError(String::from(
+                            "expected the response to be a JSON array",
+                        ))));
+                    }
+                }
+                continue;
+            }
+
+            if self.in_string {
+                self.element.push(byte);
+
+                if self.escaped {
+                    self.escaped = false;
+                } else if byte == b'\\' {
+                    self.escaped = true;
+                } else if byte == b'"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match byte {
+                b'"' => {
+                    self.in_string = true;
+                    self.element.push(byte);
+                }
+                b'{' | b'[' => {
+                    self.depth += 1;
+                    self.element.push(byte);
+                }
+                b'}' | b']' => {
+                    self.depth -= 1;
+
+                    if self.depth == 0 {
+                        // the end of the array itself
+                        self.complete = true;
+                        elements.extend(self.take_element());
+                    } else {
+                        self.element.push(byte);
+                    }
+                }
+                b',' if self.depth == 1 => elements.extend(self.take_element()),
+                _ => self.element.push(byte),
+            }
+        }
+
+        if let Some(max_size) = self
+            .max_element_size
+            .filter(|max_size| self.element.len() > *max_size)
+        {
+            self.complete = true;
+            self.element.clear();
+            elements.push(Err(Error::ResponseTooLarge(max_size)));
+        }
+
+        elements
+    }
+
+    /// Deserialize the element that has been read so far, if there is one
+    fn take_element<T: DeserializeOwned>(&mut self) -> Option<Result<T, Error>> {
+        let is_empty = self.element.iter().all(u8::is_ascii_whitespace);
+
+        let element = (!is_empty).then(|| {
+            serde_json::from_slice(&self.element)
+                .map_err(|err| Error::JSONParseError(err.to_string()))
+        });
+
+        self.element.clear();
+
+        element
+    }
+}
+
+/// Deserialize each element of a JSON array as it arrives in `bytes`, ending the stream with
+/// an error if a single element is larger than `max_element_size`
+pub(crate) fn json_array_stream<T: DeserializeOwned + Send + 'static>(
+    bytes: impl Stream<Item = Result<Bytes, reqwest::Error>> + Send + 'static,
+    max_element_size: Option<usize>,
+) -> impl Stream<Item = Result<T, Error>> + Send + 'static {
+    let state = (
+        Box::pin(bytes),
+        ArraySplitter {
+            max_element_size,
+            ..ArraySplitter::default()
+        },
+        VecDeque::new(),
+        false,
+    );
+
+    futures::stream::unfold(
+        state,
+        |(mut bytes, mut splitter, mut pending, mut finished)| async move {
+            loop {
+                if let Some(element) = pending.pop_front() {
+                    return Some((element, (bytes, splitter, pending, finished)));
+                }
+
+                if finished {
+                    return None;
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => pending.extend(splitter.push(&chunk)),
+                    Some(Err(err)) => {
+                        finished = true;
+                        pending.push_back(Err(err.into()));
+                    }
+                    None => {
+                        finished = true;
+
+                        if !splitter.complete {
+                            pending.push_back(Err(Error::JSONParseError(String::from(
+                                "the response ended before the end of the JSON array",
+                            ))));
+                        }
+                    }
+                }
+            }
+        },
+    )
+}
@@ -1,10 +1,14 @@
 //! Interacting with the language identification endpoint
 
 use crate::{client::WitClient, errors::Error};
+use futures::{StreamExt, TryStreamExt};
 use reqwest::Method;
 use serde::Deserialize;
 use serde_json::Value;
 
+/// How many requests `detect_languages_batch` keeps in flight at once
+const BATCH_CONCURRENCY: usize = 4;
+
 /// A response from the language endpoint
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct LanguageResponse {
@@ -30,25 +34,68 @@ impl WitClient {
     /// # use wit_ai_rs::client::WitClient;
     /// # use wit_ai_rs::language::LanguageResponse;
     /// # let wit_client = WitClient::new(String::new(), String::new());
-    /// let response: LanguageResponse = wit_client.language("some query sentence".to_string(), 1)
+    /// let response: LanguageResponse = wit_client.language("some query sentence", 1)
     ///     .await
     ///     .unwrap();
     /// # })
     /// ```
-    pub async fn language(&self, query: String, limit: u16) -> Result<LanguageResponse, Error> {
-        if !(1..=8).contains(&limit) {
-            return Err(Error::InvalidArgument(format!(
-                "limit must be between 1 and 8 inclusive, got {limit}",
-            )));
-        }
+    pub async fn language(
+        &self,
+        query: impl Into<String>,
+        limit: u16,
+    ) -> Result<LanguageResponse, Error> {
+        validate_limit(limit)?;
 
         let mut url_params = Vec::new();
 
-        url_params.push((String::from("q"), query));
+        url_params.push((String::from("q"), query.into()));
 
         url_params.push((String::from("n"), limit.to_string()));
 
         self.make_request(Method::GET, "/language", url_params, Option::<Value>::None)
             .await
     }
+
+    /// Detect the language of each of `queries`, returning the responses in the same order as
+    /// the queries. `limit` is the same as for `language`.
+    ///
+    /// Wit only supports the language endpoint as a GET with a single query, so this sends one
+    /// request per query, with a few in flight at once. It fails with the first error returned
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::language::LanguageResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let responses: Vec<LanguageResponse> = wit_client
+    ///     .detect_languages_batch(vec!["bonjour".to_string(), "hello".to_string()], 1)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn detect_languages_batch(
+        &self,
+        queries: Vec<String>,
+        limit: u16,
+    ) -> Result<Vec<LanguageResponse>, Error> {
+        validate_limit(limit)?;
+
+        futures::stream::iter(queries)
+            .map(|query| self.language(query, limit))
+            .buffered(BATCH_CONCURRENCY)
+            .try_collect()
+            .await
+    }
+}
+
+/// Wit accepts between 1 and 8 detected locales
+fn validate_limit(limit: u16) -> Result<(), Error> {
+    if !(1..=8).contains(&limit) {
+        return Err(Error::InvalidArgument(format!(
+            "limit must be between 1 and 8 inclusive, got {limit}",
+        )));
+    }
+
+    Ok(())
 }
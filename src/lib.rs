@@ -6,7 +6,7 @@
 //! by instantiating a WitClient:
 //! ```rust
 //! # use wit_ai_rs::client::WitClient;
-//! let wit_client = WitClient::new("TOKEN".to_string(), "20240215".to_string());
+//! let wit_client = WitClient::new("TOKEN", "20240215");
 //! ```
 //!
 //! Specific endpoints can be called using various methods of the WitClient struct, for
@@ -17,25 +17,38 @@
 //! # use wit_ai_rs::message::{MessageResponse, MessageOptions};
 //! # let wit_client = WitClient::new(String::new(), String::new());
 //! let response: MessageResponse = wit_client
-//!     .message("Some query sentence".to_string(), MessageOptions::default())
+//!     .message("Some query sentence", MessageOptions::default())
 //!     .await
 //!     .unwrap();
 //! # })
 //! ```
 //! Examples for most methods can be found in their respective modules. For each of these examples,
 //! assume that `wit_client` is a valid WitClient.
+//!
+//! The most commonly used types can be imported at once with `use wit_ai_rs::prelude::*;`
 
 #![warn(missing_docs)]
 
+mod approx;
+pub mod apps;
+pub mod builtins;
 pub mod client;
 pub mod common_types;
+pub mod config;
+mod deserialize;
 pub mod dictation;
+pub mod diff;
 pub mod entities;
 pub mod errors;
+pub mod evaluation;
 pub mod intents;
+mod json_stream;
 pub mod language;
 pub mod message;
+pub mod prelude;
+pub mod provision;
 pub mod speech;
+pub mod synthesize;
 pub mod traits;
 pub mod utterances;
 
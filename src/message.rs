@@ -1,13 +1,24 @@
 //! Interacting with the message endpoint
 
-use crate::{client::WitClient, errors::Error, DynamicEntities};
+use crate::{
+    approx,
+    client::{ResponseMeta, WitClient},
+    errors::Error,
+    Confidence, DynamicEntities,
+};
 use reqwest::Method;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// The maximum length of a URL sent to the message endpoint. Wit only accepts messages as a
+/// GET request, with the query, context, and dynamic entities in the URL, and many servers and
+/// proxies reject URLs longer than this, so a longer request fails early with
+/// `Error::InvalidArgument` instead
+pub const MAX_MESSAGE_URL_LENGTH: usize = 8192;
+
 /// Context that may be sent with a message
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Context {
     // serialized version of ContextBuilder, since Context will be passed as a serialized string in the url params
     reference_time: Option<String>,
@@ -45,8 +56,8 @@ impl ContextBuilder {
     /// Set the reference time local date and time of the user, in ISO8601 format (more specifically, RFC3339).
     /// Do not use UTC time, which would defeat the purpose of this field.
     /// Example: "2014-10-30T12:18:45-07:00"
-    pub fn reference_time(mut self, reference_time: String) -> Self {
-        self.reference_time = Some(reference_time);
+    pub fn reference_time(mut self, reference_time: impl Into<String>) -> Self {
+        self.reference_time = Some(reference_time.into());
         self
     }
 
@@ -56,16 +67,16 @@ impl ContextBuilder {
     /// are provided, wit will use the default timezone of your app, which you can set in 'Settings'
     /// in the web console.
     /// Example: "America/Los_Angeles"
-    pub fn timezone(mut self, timezone: String) -> Self {
-        self.timezone = Some(timezone);
+    pub fn timezone(mut self, timezone: impl Into<String>) -> Self {
+        self.timezone = Some(timezone.into());
         self
     }
 
     /// Set the locale of the user: the first 2 letters must be a valid ISO639-1 language, followed by an underscore,
     /// followed by a valid ISO3166 alpha2 country code.
     /// Example: "en_US".
-    pub fn locale(mut self, value: String) -> Self {
-        self.locale = Some(value);
+    pub fn locale(mut self, value: impl Into<String>) -> Self {
+        self.locale = Some(value.into());
         self
     }
 
@@ -94,8 +105,8 @@ impl Default for ContextBuilder {
     }
 }
 
-/// Coordinates for `Context`
-#[derive(Debug, Serialize)]
+/// Coordinates for `Context`, also returned for resolved `wit$location` entities
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
 pub struct Coordinates {
     lat: f64,
     long: f64,
@@ -109,15 +120,65 @@ impl Coordinates {
             long: longitude,
         }
     }
+
+    /// The latitude, in degrees
+    pub fn latitude(&self) -> f64 {
+        self.lat
+    }
+
+    /// The longitude, in degrees
+    pub fn longitude(&self) -> f64 {
+        self.long
+    }
 }
 
-/// Options to include with a request to the message endpoint
-#[derive(Debug, Default)]
+/// Query parameters that are set by the crate, which cannot be overridden with
+/// `MessageOptionsBuilder::extra_param`
+const RESERVED_PARAMS: [&str; 7] = ["v", "q", "tag", "n", "verbose", "context", "entities"];
+
+/// Options to include with a request to the message endpoint, or the speech endpoint
+/// (see `WitClient::speech_with_options`)
+#[derive(Debug, Clone, Default)]
 pub struct MessageOptions {
     tag: Option<String>,
     n: Option<u16>,
+    verbose: Option<bool>,
     context: Option<Context>,
     dynamic_entities: Option<DynamicEntities>,
+    extra_params: Vec<(String, String)>,
+    normalize_query: bool,
+    validate_dynamic_entities: bool,
+}
+
+impl MessageOptions {
+    /// The URL parameters representing these options
+    pub(crate) fn get_url_params(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut url_params = Vec::new();
+
+        if let Some(tag) = &self.tag {
+            url_params.push((String::from("tag"), tag.clone()));
+        }
+
+        if let Some(n) = self.n {
+            url_params.push((String::from("n"), n.to_string()));
+        }
+
+        if let Some(verbose) = self.verbose {
+            url_params.push((String::from("verbose"), verbose.to_string()));
+        }
+
+        if let Some(context) = &self.context {
+            url_params.push((String::from("context"), context.get_serialized()));
+        }
+
+        if let Some(entities) = &self.dynamic_entities {
+            url_params.push((String::from("entities"), entities.get_serialized()?))
+        }
+
+        url_params.extend(self.extra_params.iter().cloned());
+
+        Ok(url_params)
+    }
 }
 
 /// Builder for `MessageOptions`
@@ -125,8 +186,12 @@ pub struct MessageOptions {
 pub struct MessageOptionsBuilder {
     tag: Option<String>,
     n: Option<u16>,
+    verbose: Option<bool>,
     context: Option<Context>,
     dynamic_entities: Option<DynamicEntities>,
+    extra_params: Vec<(String, String)>,
+    normalize_query: bool,
+    validate_dynamic_entities: bool,
 }
 
 impl MessageOptionsBuilder {
@@ -135,14 +200,26 @@ impl MessageOptionsBuilder {
         MessageOptionsBuilder {
             tag: None,
             n: None,
+            verbose: None,
             context: None,
             dynamic_entities: None,
+            extra_params: Vec::new(),
+            normalize_query: false,
+            validate_dynamic_entities: false,
         }
     }
 
-    /// Set the tag for the message request (tag indicates version).
-    pub fn tag(mut self, tag: String) -> Self {
-        self.tag = Some(tag);
+    /// Set the tag for the message request, which selects a tagged version of the app.
+    /// A tag is a frozen snapshot of the app's trained model, so requests with a tag are
+    /// unaffected by later training, which allows running production traffic on a pinned
+    /// model while testing a newer one under a different tag (or no tag, which uses the
+    /// latest model).
+    ///
+    /// This is unrelated to the API version given to `WitClient::new` and sent as the `v`
+    /// parameter, which only controls the shape of requests and responses. Both are sent
+    /// with every message request.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
         self
     }
 
@@ -159,25 +236,77 @@ impl MessageOptionsBuilder {
         Ok(self)
     }
 
+    /// Request extra debug information about how wit processed the message, which is returned
+    /// in `MessageResponse::debug`. Useful for understanding why an entity wasn't resolved
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = Some(verbose);
+        self
+    }
+
     /// Set the context for the message
     pub fn context(mut self, context: Context) -> Self {
         self.context = Some(context);
         self
     }
 
-    /// Sets the dynamic entities for the message
+    /// Sets the dynamic entities for the message. They are sent in the URL, so the message
+    /// fails with `Error::InvalidArgument` if they make it longer than `MAX_MESSAGE_URL_LENGTH`
     pub fn dynamic_entities(mut self, entities: DynamicEntities) -> Self {
         self.dynamic_entities = Some(entities);
         self
     }
 
+    /// Trim the query and collapse every run of whitespace inside it into a single space
+    /// before sending it, so that stray spaces in user-typed input do not change the result or
+    /// count toward the query length limit. Off by default, so that the query is sent exactly
+    /// as given. This has no effect on speech requests, which have no text query
+    pub fn normalize_query(mut self, normalize_query: bool) -> Self {
+        self.normalize_query = normalize_query;
+        self
+    }
+
+    /// Before sending the message, check that every dynamic entity extends a keyword entity
+    /// of the app, since wit only supports dynamic keywords for those. If one does not, an
+    /// `InvalidArgument` error is returned (or `EntityNotFound` if the entity does not exist)
+    /// and the message is not sent. Off by default, since this fetches each entity with an
+    /// extra request before every message. Only `message` performs this check
+    pub fn validate_dynamic_entities(mut self, validate_dynamic_entities: bool) -> Self {
+        self.validate_dynamic_entities = validate_dynamic_entities;
+        self
+    }
+
+    /// Add a query parameter that is sent verbatim with the request, for parameters that wit
+    /// supports but this crate does not model yet (such as beta flags). Returns an error if
+    /// `name` is a parameter set by the crate itself (`v`, `q`, or one of the other options
+    /// on this builder), since those cannot be overridden
+    pub fn extra_param(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Result<Self, Error> {
+        let name = name.into();
+
+        if RESERVED_PARAMS.contains(&name.as_str()) {
+            return Err(Error::InvalidArgument(format!(
+                "the `{name}` parameter is set by the crate and cannot be overridden"
+            )));
+        }
+
+        self.extra_params.push((name, value.into()));
+        Ok(self)
+    }
+
     /// Turn this `MessageOptionsBuilder` into a `MessageOptions`
     pub fn build(self) -> MessageOptions {
         MessageOptions {
             tag: self.tag,
             n: self.n,
+            verbose: self.verbose,
             context: self.context,
             dynamic_entities: self.dynamic_entities,
+            extra_params: self.extra_params,
+            normalize_query: self.normalize_query,
+            validate_dynamic_entities: self.validate_dynamic_entities,
         }
     }
 }
@@ -203,23 +332,416 @@ pub struct MessageResponse {
     /// HashMap of traits.
     /// Each trait will contain a vector of values even if there is only one value returned.
     pub traits: HashMap<String, Vec<MessageTrait>>,
+    /// Debug information returned by wit when `MessageOptionsBuilder::verbose` is set.
+    /// Its shape is not documented by wit, so it is left as raw JSON
+    #[serde(default)]
+    pub debug: Option<Value>,
+}
+
+impl MessageResponse {
+    /// The intent wit is most confident in, if any intents were returned
+    pub fn top_intent(&self) -> Option<&MessageIntent> {
+        top_intent(&self.intents)
+    }
+
+    /// The intent wit is most confident in, if its confidence is at least `threshold`
+    pub fn intent_above(&self, threshold: f64) -> Option<&MessageIntent> {
+        intent_above(&self.intents, threshold)
+    }
+
+    /// The n-best intents (see `MessageOptionsBuilder::n`) paired with their rank, starting
+    /// at 1 for the most confident intent. Wit already sorts intents by confidence, but this
+    /// sorts them again rather than relying on it
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::message::MessageResponse;
+    /// fn print_candidates(response: &MessageResponse) {
+    ///     for (rank, intent) in response.nbest_intents() {
+    ///         println!("{rank}. {} ({})", intent.name, intent.confidence);
+    ///     }
+    /// }
+    /// ```
+    pub fn nbest_intents(&self) -> impl Iterator<Item = (usize, &MessageIntent)> {
+        let mut intents: Vec<&MessageIntent> = self.intents.iter().collect();
+
+        intents.sort_by(|a, b| {
+            b.confidence
+                .into_inner()
+                .total_cmp(&a.confidence.into_inner())
+        });
+
+        intents
+            .into_iter()
+            .enumerate()
+            .map(|(index, intent)| (index + 1, intent))
+    }
+
+    /// Like `nbest_intents`, but only the `k` most confident intents
+    pub fn top_k(&self, k: usize) -> impl Iterator<Item = (usize, &MessageIntent)> {
+        self.nbest_intents().take(k)
+    }
+
+    /// Whether this response matches `other`, allowing every confidence to differ by up to
+    /// `epsilon`. Everything else must match exactly, although the order of the entity and
+    /// trait maps does not matter. Useful in tests, where confidences may jitter between runs
+    pub fn approx_eq(&self, other: &MessageResponse, epsilon: f64) -> bool {
+        self.approx_diff(other, epsilon).is_empty()
+    }
+
+    /// Every difference between this response and `other` that `approx_eq` would reject, one
+    /// per line, each starting with the path to the field (ex. `intents[0].confidence`).
+    /// Useful for readable test failures instead of printing both responses in full
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use wit_ai_rs::message::MessageResponse;
+    /// # fn check(actual: MessageResponse, expected: MessageResponse) {
+    /// let differences = actual.approx_diff(&expected, 0.01);
+    ///
+    /// assert!(differences.is_empty(), "{}", differences.join("\n"));
+    /// # }
+    /// ```
+    pub fn approx_diff(&self, other: &MessageResponse, epsilon: f64) -> Vec<String> {
+        approx::message_differences(self, other, epsilon)
+    }
+
+    /// Compare this response to a `baseline` response for the same query, such as one saved
+    /// before the app's training data changed. Unlike `approx_diff`, this only reports changes
+    /// that affect how the message is understood: a different top intent, entities (by
+    /// `name:role`) that were added or removed, and intents or entities whose confidence moved
+    /// by more than `confidence_tolerance`. Only the most confident value of each entity is
+    /// compared
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # use wit_ai_rs::message::MessageResponse;
+    /// # fn check(response: MessageResponse, baseline: MessageResponse) {
+    /// let diff = response.diff(&baseline, 0.1);
+    ///
+    /// assert!(diff.is_empty(), "{diff}");
+    /// # }
+    /// ```
+    pub fn diff(&self, baseline: &MessageResponse, confidence_tolerance: f64) -> MessageDiff {
+        let name_of = |intent: Option<&MessageIntent>| intent.map(|intent| intent.name.clone());
+
+        let (baseline_intent, intent) =
+            (name_of(baseline.top_intent()), name_of(self.top_intent()));
+
+        let significant = |baseline: Confidence, current: Confidence| {
+            (current.into_inner() - baseline.into_inner()).abs() > confidence_tolerance
+        };
+
+        let intent_confidence_changes = baseline
+            .intents
+            .iter()
+            .filter_map(|baseline_intent| {
+                let intent = self
+                    .intents
+                    .iter()
+                    .find(|intent| intent.name == baseline_intent.name)?;
+
+                significant(baseline_intent.confidence, intent.confidence).then(|| {
+                    ConfidenceChange {
+                        name: intent.name.clone(),
+                        baseline: baseline_intent.confidence,
+                        current: intent.confidence,
+                    }
+                })
+            })
+            .collect();
+
+        let mut added_entities: Vec<String> = self
+            .entities
+            .keys()
+            .filter(|key| !baseline.entities.contains_key(*key))
+            .cloned()
+            .collect();
+        added_entities.sort();
+
+        let mut removed_entities: Vec<String> = baseline
+            .entities
+            .keys()
+            .filter(|key| !self.entities.contains_key(*key))
+            .cloned()
+            .collect();
+        removed_entities.sort();
+
+        let mut entity_confidence_changes: Vec<ConfidenceChange> = baseline
+            .entities
+            .iter()
+            .filter_map(|(key, baseline_values)| {
+                let baseline_entity = baseline_values.first()?;
+                let entity = self.entities.get(key)?.first()?;
+
+                significant(baseline_entity.confidence, entity.confidence).then(|| {
+                    ConfidenceChange {
+                        name: key.clone(),
+                        baseline: baseline_entity.confidence,
+                        current: entity.confidence,
+                    }
+                })
+            })
+            .collect();
+        entity_confidence_changes.sort_by(|a, b| a.name.cmp(&b.name));
+
+        MessageDiff {
+            intent_change: (baseline_intent != intent).then_some(IntentChange {
+                baseline: baseline_intent,
+                current: intent,
+            }),
+            added_entities,
+            removed_entities,
+            intent_confidence_changes,
+            entity_confidence_changes,
+        }
+    }
+
+    /// Deserialize the most confident value of the trait with the given name into `T`, such as
+    /// an enum of the trait's known values. Returns `None` if the trait was not found in the
+    /// message, and an error if its value does not match `T`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::message::MessageResponse;
+    /// #[derive(serde::Deserialize)]
+    /// #[serde(rename_all = "lowercase")]
+    /// enum Sentiment {
+    ///     Positive,
+    ///     Neutral,
+    ///     Negative,
+    /// }
+    ///
+    /// fn is_unhappy(response: &MessageResponse) -> bool {
+    ///     matches!(
+    ///         response.trait_as::<Sentiment>("wit$sentiment"),
+    ///         Some(Ok(Sentiment::Negative))
+    ///     )
+    /// }
+    /// ```
+    pub fn trait_as<T: DeserializeOwned>(&self, name: &str) -> Option<Result<T, Error>> {
+        let message_trait = self.traits.get(name)?.iter().max_by(|a, b| {
+            a.confidence
+                .into_inner()
+                .total_cmp(&b.confidence.into_inner())
+        })?;
+
+        Some(
+            serde_json::from_value(message_trait.value.clone()).map_err(|err| {
+                Error::JSONParseError(format!("invalid value for trait {name}: {err}"))
+            }),
+        )
+    }
+
+    /// Reduce the response to its top intent and a string value for each entity, for apps
+    /// that only need to know what was asked for and with which parameters.
+    ///
+    /// This is lossy: confidences, n-best intents, traits, and all but the first value of each
+    /// entity are dropped. Each slot is keyed by the entity's role (ex. `datetime` for
+    /// `wit$datetime:datetime`), and its value is the entity's resolved value when that is a
+    /// string, number, or boolean, or the text of the entity as it appears in the query
+    /// otherwise (ex. for intervals).
+    ///
+    /// Different entities can share a role (ex. `wit$location:destination` and
+    /// `airport:destination`). The slot then holds the one wit is most confident in, with ties
+    /// going to the entity whose `name:role` sorts first, so the result does not depend on
+    /// the order of the response's entities
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::message::MessageResponse;
+    /// fn destination(response: &MessageResponse) -> Option<String> {
+    ///     let simple = response.to_simple();
+    ///
+    ///     if simple.intent.as_deref() != Some("book_flight") {
+    ///         return None;
+    ///     }
+    ///
+    ///     simple.slots.get("destination").cloned()
+    /// }
+    /// ```
+    pub fn to_simple(&self) -> SimpleResult {
+        let mut winners: HashMap<&str, (&str, &MessageEntity)> = HashMap::new();
+
+        for (key, values) in &self.entities {
+            let Some(entity) = values.first() else {
+                continue;
+            };
+
+            let replaces = winners
+                .get(entity.role.as_str())
+                .is_none_or(|(winner_key, winner)| {
+                    entity.confidence > winner.confidence
+                        || (entity.confidence == winner.confidence && key.as_str() < *winner_key)
+                });
+
+            if replaces {
+                winners.insert(&entity.role, (key, entity));
+            }
+        }
+
+        let slots = winners
+            .into_iter()
+            .map(|(role, (_, entity))| {
+                let value = match entity.resolved_value() {
+                    Some(Value::String(value)) => value.clone(),
+                    Some(value @ (Value::Number(_) | Value::Bool(_))) => value.to_string(),
+                    _ => entity.body.clone(),
+                };
+
+                (role.to_owned(), value)
+            })
+            .collect();
+
+        SimpleResult {
+            intent: self.top_intent().map(|intent| intent.name.clone()),
+            slots,
+        }
+    }
+}
+
+/// A simplified message response, produced by `MessageResponse::to_simple`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleResult {
+    /// The name of the intent wit is most confident in, if any
+    pub intent: Option<String>,
+    /// The value of each entity, keyed by its role (see `MessageResponse::to_simple` for
+    /// entities that share a role)
+    pub slots: HashMap<String, String>,
+}
+
+/// How a message response differs from a baseline, produced by `MessageResponse::diff`.
+/// Printing it lists every change on its own line
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDiff {
+    /// The change in the most confident intent, if it is different
+    pub intent_change: Option<IntentChange>,
+    /// Entities (by `name:role`) that are in the response but not the baseline, sorted
+    pub added_entities: Vec<String>,
+    /// Entities (by `name:role`) that are in the baseline but not the response, sorted
+    pub removed_entities: Vec<String>,
+    /// Intents in both responses whose confidence changed by more than the tolerance
+    pub intent_confidence_changes: Vec<ConfidenceChange>,
+    /// Entities (by `name:role`) in both responses whose confidence changed by more than the
+    /// tolerance, sorted
+    pub entity_confidence_changes: Vec<ConfidenceChange>,
+}
+
+impl MessageDiff {
+    /// Whether the response is the same as the baseline, within the confidence tolerance
+    pub fn is_empty(&self) -> bool {
+        self.intent_change.is_none()
+            && self.added_entities.is_empty()
+            && self.removed_entities.is_empty()
+            && self.intent_confidence_changes.is_empty()
+            && self.entity_confidence_changes.is_empty()
+    }
+}
+
+impl std::fmt::Display for MessageDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        let mut lines = Vec::new();
+
+        if let Some(change) = &self.intent_change {
+            let name = |intent: &Option<String>| intent.as_deref().unwrap_or("(none)").to_owned();
+
+            lines.push(format!(
+                "intent: {} -> {}",
+                name(&change.baseline),
+                name(&change.current)
+            ));
+        }
+
+        lines.extend(
+            self.added_entities
+                .iter()
+                .map(|entity| format!("added entity: {entity}")),
+        );
+        lines.extend(
+            self.removed_entities
+                .iter()
+                .map(|entity| format!("removed entity: {entity}")),
+        );
+        lines.extend(
+            self.intent_confidence_changes
+                .iter()
+                .map(|change| format!("intent {change}")),
+        );
+        lines.extend(
+            self.entity_confidence_changes
+                .iter()
+                .map(|change| format!("entity {change}")),
+        );
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// A change in the most confident intent between a baseline and a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntentChange {
+    /// The intent of the baseline, if it had one
+    pub baseline: Option<String>,
+    /// The intent of the response, if it has one
+    pub current: Option<String>,
+}
+
+/// A change in the confidence of an intent or entity between a baseline and a response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfidenceChange {
+    /// The name of the intent, or the `name:role` of the entity
+    pub name: String,
+    /// The confidence in the baseline
+    pub baseline: Confidence,
+    /// The confidence in the response
+    pub current: Confidence,
+}
+
+impl std::fmt::Display for ConfidenceChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} confidence: {} -> {}",
+            self.name, self.baseline, self.current
+        )
+    }
+}
+
+/// The intent with the highest confidence, without assuming the intents are sorted
+pub(crate) fn top_intent(intents: &[MessageIntent]) -> Option<&MessageIntent> {
+    intents.iter().max_by(|a, b| {
+        a.confidence
+            .into_inner()
+            .total_cmp(&b.confidence.into_inner())
+    })
+}
+
+/// The intent with the highest confidence, if that confidence is at least `threshold`
+pub(crate) fn intent_above(intents: &[MessageIntent], threshold: f64) -> Option<&MessageIntent> {
+    top_intent(intents).filter(|intent| intent.confidence.into_inner() >= threshold)
 }
 
 /// Intents extracted from the message request
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct MessageIntent {
     /// The id of the intent
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the intent
     pub name: String,
     /// Wit's confidence in the intent
-    pub confidence: f64,
+    pub confidence: Confidence,
 }
 
 /// Entities associated with the message request
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct MessageEntity {
     /// The entity id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The entity name
     pub name: String,
@@ -232,47 +754,207 @@ pub struct MessageEntity {
     /// The entity as it appears in the query
     pub body: String,
     /// Wit's confidence in the entity
-    pub confidence: f64,
-    /// A HashMap of sub-entities
-    pub entities: HashMap<String, MessageEntity>,
+    pub confidence: Confidence,
+    /// Sub-entities, for composite entities. Like the entities of a `MessageResponse`, they
+    /// are keyed by `entity:role`, and each key may have several values
+    pub entities: HashMap<String, Vec<MessageEntity>>,
     /// The value of the entity (this does not exist when the entity's value is a range)
     pub value: Option<Value>,
+    /// The unit of the value, for entities that have one (ex. "$" for wit$amount_of_money)
+    pub unit: Option<String>,
+    /// The level of precision/specificity of the value, for datetime values. Ex. `Grain::Day`
+    pub grain: Option<Grain>,
+    /// The value converted to a standard unit, for entities that have one (ex. seconds for
+    /// wit$duration)
+    pub normalized: Option<NormalizedValue>,
     /// The lower end of the range for interval-type values.
     /// This does not exist when the value type is not interval, or when the interval only has an upper bound
     pub from: Option<IntervalEndpoint>,
     /// The upper end of the range for interval-type values.
     /// This does not exist when the value type is not interval, or when the interval only has a lower bound
     pub to: Option<IntervalEndpoint>,
-    // a little complicated to implement in tests
-    // pub values: Option<Vec<Value>>,
+    /// Every possible value of the entity, for entities that can resolve to more than one
+    /// (ex. "at 5" for wit$datetime). Some built-in entities only return this, and no `value`
+    pub values: Option<Vec<Value>>,
+    /// Details wit resolved the value to, for entities it can look up (ex. the place a
+    /// wit$location refers to). Its shape depends on the entity, so it is left as raw JSON
+    pub resolved: Option<Value>,
+}
+
+impl MessageEntity {
+    /// The first sub-entity with the given entity name and role. If `role` is `None`, the
+    /// entity's default role is preferred (its name without any `wit$` prefix), and
+    /// otherwise any role is accepted
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::message::MessageEntity;
+    /// fn reminder_time(reminder: &MessageEntity) -> Option<&MessageEntity> {
+    ///     reminder.sub_entity("wit$datetime", None)
+    /// }
+    /// ```
+    pub fn sub_entity(&self, entity: &str, role: Option<&str>) -> Option<&MessageEntity> {
+        let default_role = entity.strip_prefix("wit$").unwrap_or(entity);
+
+        let key = format!("{entity}:{}", role.unwrap_or(default_role));
+
+        if let Some(sub_entity) = self.entities.get(&key).and_then(|values| values.first()) {
+            return Some(sub_entity);
+        }
+
+        if role.is_some() {
+            return None;
+        }
+
+        // sorted, so that the same role is chosen every time
+        let mut keys: Vec<&String> = self
+            .entities
+            .keys()
+            .filter(|key| key.split_once(':').is_some_and(|(name, _)| name == entity))
+            .collect();
+        keys.sort();
+
+        keys.into_iter().find_map(|key| self.entities[key].first())
+    }
+
+    /// The value of the entity, falling back to the first of `values` when there is no
+    /// `value`, so that single- and multi-value entities can be read the same way. Each
+    /// element of `values` is usually an object with its own `value` field (along with
+    /// details like its grain), in which case that inner value is returned
+    pub fn resolved_value(&self) -> Option<&Value> {
+        if let Some(value) = &self.value {
+            return Some(value);
+        }
+
+        let first = self.values.as_ref()?.first()?;
+
+        Some(first.get("value").unwrap_or(first))
+    }
+
+    /// Every sub-entity, regardless of name and role
+    pub fn sub_entities(&self) -> impl Iterator<Item = &MessageEntity> {
+        self.entities.values().flatten()
+    }
+
+    /// The interval this entity represents, if it is an interval-type value with at least
+    /// one bound. Ex. "after Tuesday" only has a lower bound, while "between Tuesday and
+    /// Friday" has both
+    pub fn interval(&self) -> Option<Interval> {
+        if self.from.is_none() && self.to.is_none() {
+            return None;
+        }
+
+        Some(Interval {
+            from: self.from.clone(),
+            to: self.to.clone(),
+        })
+    }
+}
+
+/// A value converted to a standard unit by wit
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+pub struct NormalizedValue {
+    /// The converted value
+    pub value: f64,
+    /// The standard unit of the value. Ex. "second"
+    pub unit: String,
 }
 
 /// The data associated with an interval endpoint
-#[derive(Debug, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq)]
 pub struct IntervalEndpoint {
     /// The value of the unit given
     pub unit: Option<String>,
-    /// The level of precision/specificity of the value. Ex. "day"
-    pub grain: Option<String>,
+    /// The level of precision/specificity of the value. Ex. `Grain::Day`
+    pub grain: Option<Grain>,
     /// The value of the interval endpoint
     pub value: Value,
 }
 
+/// An interval value, which may be missing either its lower or upper bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interval {
+    /// The lower bound of the interval, if it has one
+    pub from: Option<IntervalEndpoint>,
+    /// The upper bound of the interval, if it has one
+    pub to: Option<IntervalEndpoint>,
+}
+
+impl Interval {
+    /// Whether the interval is missing either of its bounds
+    pub fn is_open_ended(&self) -> bool {
+        self.from.is_none() || self.to.is_none()
+    }
+}
+
+/// The level of precision/specificity of a datetime value
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Grain {
+    /// Precise to the second
+    Second,
+    /// Precise to the minute
+    Minute,
+    /// Precise to the hour
+    Hour,
+    /// Precise to the day
+    Day,
+    /// Precise to the week
+    Week,
+    /// Precise to the month
+    Month,
+    /// Precise to the quarter
+    Quarter,
+    /// Precise to the year
+    Year,
+    /// A grain not known to this crate, containing the grain string returned by wit
+    Other(String),
+}
+
+impl From<String> for Grain {
+    fn from(grain: String) -> Self {
+        match grain.as_str() {
+            "second" => Self::Second,
+            "minute" => Self::Minute,
+            "hour" => Self::Hour,
+            "day" => Self::Day,
+            "week" => Self::Week,
+            "month" => Self::Month,
+            "quarter" => Self::Quarter,
+            "year" => Self::Year,
+            _ => Self::Other(grain),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Grain {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
 /// A trait determined from the message request
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct MessageTrait {
     /// The id of the trait
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The value of the trait
     pub value: Value,
     /// Wit's confidence in the trait
-    pub confidence: f64,
+    pub confidence: Confidence,
 }
 
 impl WitClient {
     /// Send a request to wit's /message endpoint, using a request builder `MessageRequestBuilder`.
     /// Information regarding each argument that can be used in `MessageRequestBuilder` can be found
-    /// in the documentation for that struct. Note that query may be no more than 280 characters.
+    /// in the documentation for that struct. Note that query may be no more than 280 characters,
+    /// and that serialized dynamic entities may be no larger than roughly
+    /// `MAX_DYNAMIC_ENTITIES_LENGTH` bytes, otherwise `Error::InvalidArgument` is returned.
+    ///
+    /// Parameters are sent in the URL of a GET request, which is the only form of the endpoint
+    /// that wit documents. If the URL would be longer than `MAX_MESSAGE_URL_LENGTH` (usually
+    /// because of large dynamic entities), `Error::InvalidArgument` is returned without sending
+    /// the request.
     ///
     /// Example (simple message, no additional options):
     /// ```rust,no_run
@@ -281,7 +963,7 @@ impl WitClient {
     /// # use wit_ai_rs::message::{MessageResponse, MessageOptions};
     /// # let wit_client = WitClient::new(String::new(), String::new());
     /// let response: MessageResponse = wit_client
-    ///     .message("some query sentence".to_string(), MessageOptions::default())
+    ///     .message("some query sentence", MessageOptions::default())
     ///     .await
     ///     .unwrap();
     ///
@@ -297,8 +979,8 @@ impl WitClient {
     /// # };
     /// # let wit_client = WitClient::new(String::new(), String::new());
     /// let context: Context = ContextBuilder::new()
-    ///     .timezone("America/Los_Angeles".to_string())
-    ///     .locale("en_US".to_string())
+    ///     .timezone("America/Los_Angeles")
+    ///     .locale("en_US")
     ///     .build();
     ///
     /// let message_options: MessageOptions = MessageOptionsBuilder::new()
@@ -308,7 +990,7 @@ impl WitClient {
     ///     .build();
     ///
     /// let response: MessageResponse = wit_client
-    ///     .message("some query sentence".to_string(), message_options)
+    ///     .message("some query sentence", message_options)
     ///     .await
     ///     .unwrap();
     /// # })
@@ -326,8 +1008,8 @@ impl WitClient {
     /// # };
     /// # let wit_client = WitClient::new(String::new(), String::new());
     /// let entity = DynamicEntity::new(
-    ///     "entity_name".to_string(),
-    ///     vec![EntityKeyword::new("keyword".to_string(), vec!["synonym".to_string()])]
+    ///     "entity_name",
+    ///     vec![EntityKeyword::new("keyword", vec!["synonym".to_string()])]
     /// );
     ///
     /// let entities = DynamicEntities::new(vec![entity]);
@@ -337,37 +1019,101 @@ impl WitClient {
     ///     .build();
     ///
     /// let response: MessageResponse = wit_client
-    ///     .message("some query sentence".to_string(), options)
+    ///     .message("some query sentence", options)
     ///     .await
     ///     .unwrap();
     /// # })
     /// ```
     pub async fn message(
         &self,
-        query: String,
+        query: impl Into<String>,
         options: MessageOptions,
     ) -> Result<MessageResponse, Error> {
-        let mut url_params = Vec::new();
+        let (response, _) = self.message_with_meta(query, options).await?;
 
-        url_params.push((String::from("q"), query));
+        Ok(response)
+    }
 
-        if let Some(tag) = options.tag {
-            url_params.push((String::from("tag"), tag));
-        }
+    /// Send a request to wit's /message endpoint like `message`, but also return metadata about
+    /// the request, such as how long it took
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::{ResponseMeta, WitClient};
+    /// # use wit_ai_rs::message::{MessageResponse, MessageOptions};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let (response, meta): (MessageResponse, ResponseMeta) = wit_client
+    ///     .message_with_meta("some query sentence", MessageOptions::default())
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// println!("message took {:?}", meta.elapsed);
+    /// # })
+    /// ```
+    pub async fn message_with_meta(
+        &self,
+        query: impl Into<String>,
+        options: MessageOptions,
+    ) -> Result<(MessageResponse, ResponseMeta), Error> {
+        let mut query = query.into();
 
-        if let Some(n) = options.n {
-            url_params.push((String::from("n"), n.to_string()));
+        if let Some(dynamic_entities) = options
+            .dynamic_entities
+            .as_ref()
+            .filter(|_| options.validate_dynamic_entities)
+        {
+            self.check_keyword_entities(dynamic_entities).await?;
         }
 
-        if let Some(context) = options.context {
-            url_params.push((String::from("context"), context.get_serialized()));
+        if options.normalize_query {
+            query = query.split_whitespace().collect::<Vec<_>>().join(" ");
         }
 
-        if let Some(entities) = options.dynamic_entities {
-            url_params.push((String::from("entities"), entities.get_serialized()))
+        let mut url_params = Vec::new();
+
+        url_params.push((String::from("q"), query.clone()));
+
+        url_params.extend(options.get_url_params()?);
+
+        let url_length = self.url_length("/message", &url_params);
+
+        if url_length > MAX_MESSAGE_URL_LENGTH {
+            return Err(Error::InvalidArgument(format!(
+                "the message URL would be {url_length} bytes, which exceeds the limit of \
+                {MAX_MESSAGE_URL_LENGTH} bytes--try shortening the query or reducing the number \
+                of dynamic entity keywords and synonyms"
+            )));
         }
 
-        self.make_request(Method::GET, "/message", url_params, Option::<Value>::None)
+        self.make_request_with_meta(Method::GET, "/message", url_params, Option::<Value>::None)
             .await
     }
+
+    /// Returns an error unless every dynamic entity extends an existing keyword entity
+    async fn check_keyword_entities(
+        &self,
+        dynamic_entities: &DynamicEntities,
+    ) -> Result<(), Error> {
+        let entities = futures::future::try_join_all(
+            dynamic_entities.names().map(|name| self.get_entity(name)),
+        )
+        .await?;
+
+        for entity in entities {
+            let is_keyword_entity = entity
+                .lookups
+                .as_ref()
+                .is_some_and(|lookups| lookups.iter().any(|lookup| lookup == "keywords"));
+
+            if !is_keyword_entity {
+                return Err(Error::InvalidArgument(format!(
+                    "dynamic entities can only extend keyword entities, but `{}` is not one",
+                    entity.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
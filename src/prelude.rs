@@ -0,0 +1,31 @@
+//! Re-exports of the most commonly used types in the crate
+//!
+//! ```rust,no_run
+//! # tokio_test::block_on(async {
+//! use wit_ai_rs::prelude::*;
+//!
+//! let wit_client = WitClient::new("TOKEN", "20240215");
+//!
+//! let options: MessageOptions = MessageOptionsBuilder::new()
+//!     .context(ContextBuilder::new().timezone("America/Los_Angeles").build())
+//!     .build();
+//!
+//! let response: Result<MessageResponse, Error> = wit_client
+//!     .message("some query sentence", options)
+//!     .await;
+//! # })
+//! ```
+
+pub use crate::{
+    client::WitClient,
+    common_types::{AudioType, Confidence, DynamicEntities, DynamicEntity, EntityKeyword},
+    dictation::DictationResponse,
+    entities::{EntityResponse, NewEntityBuilder},
+    errors::Error,
+    intents::IntentResponse,
+    message::{ContextBuilder, MessageOptions, MessageOptionsBuilder, MessageResponse},
+    speech::SpeechResponse,
+    synthesize::SynthesizeRequestBuilder,
+    traits::{NewTrait, TraitResponse},
+    utterances::{GetUtterancesRequestBuilder, NewUtterance, UtteranceResponse},
+};
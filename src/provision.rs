@@ -0,0 +1,162 @@
+//! Declaratively setting up an app's entities, traits, intents, and utterances
+
+use std::collections::HashSet;
+
+use crate::{
+    client::WitClient, entities::NewEntity, errors::Error, traits::NewTrait,
+    utterances::NewUtterance,
+};
+
+/// A description of the resources an app should have
+#[derive(Debug, Default)]
+pub struct AppSpec {
+    /// Entities to create, if they do not already exist
+    pub entities: Vec<NewEntity>,
+    /// Traits to create, if they do not already exist
+    pub traits: Vec<NewTrait>,
+    /// Names of intents to create, if they do not already exist
+    pub intents: Vec<String>,
+    /// Utterances to train the app with, which may reference the entities, traits, and intents
+    pub utterances: Vec<NewUtterance>,
+}
+
+/// A step of provisioning an app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProvisionStep {
+    /// Ensuring the entity with the given name exists
+    Entity(String),
+    /// Ensuring the trait with the given name exists
+    Trait(String),
+    /// Ensuring the intent with the given name exists
+    Intent(String),
+    /// Creating the utterances
+    Utterances,
+}
+
+impl std::fmt::Display for ProvisionStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Entity(name) => write!(f, "entity {}", name),
+            Self::Trait(name) => write!(f, "trait {}", name),
+            Self::Intent(name) => write!(f, "intent {}", name),
+            Self::Utterances => write!(f, "utterances"),
+        }
+    }
+}
+
+impl WitClient {
+    /// Set up the app so that it has everything in `spec`. Entities and traits are created first,
+    /// then intents, then utterances, so that everything an utterance references exists before
+    /// it is created. Entities, traits, and intents that already exist are left unchanged, so
+    /// this can safely be run repeatedly. Each of the entity, trait, and intent lists is fetched
+    /// at most once, rather than once per item as separate `ensure_*` calls would.
+    ///
+    /// If a step fails, `Error::ProvisionError` is returned with the failed step and its error.
+    /// Steps before it will already have been applied.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::entities::NewEntityBuilder;
+    /// # use wit_ai_rs::provision::AppSpec;
+    /// # use wit_ai_rs::utterances::{NewUtterance, NewUtteranceEntity};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let spec = AppSpec {
+    ///     entities: vec![NewEntityBuilder::new("wit$number").build()],
+    ///     intents: vec!["set_volume".to_string()],
+    ///     utterances: vec![NewUtterance::new(
+    ///         "make the volume 30",
+    ///         vec![NewUtteranceEntity::new(
+    ///             "wit$number:number",
+    ///             16,
+    ///             18,
+    ///             "30",
+    ///             vec![],
+    ///         )],
+    ///         vec![],
+    ///         Some("set_volume".to_string()),
+    ///     )],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// wit_client.provision(spec).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn provision(&self, spec: AppSpec) -> Result<(), Error> {
+        let failed_at = |step: ProvisionStep| move |err| Error::ProvisionError(step, Box::new(err));
+
+        // each list is fetched once, when the spec has anything of that kind, and failures to
+        // fetch it are attributed to the first item that needed it
+        if let Some(first) = spec.entities.first() {
+            let step = ProvisionStep::Entity(first.name.clone());
+            let mut existing: HashSet<String> = self
+                .get_entities()
+                .await
+                .map_err(failed_at(step))?
+                .into_iter()
+                .map(|entity| entity.name)
+                .collect();
+
+            for entity in spec.entities {
+                if existing.contains(&entity.name) {
+                    continue;
+                }
+                let step = ProvisionStep::Entity(entity.name.clone());
+                let created = self.create_entity(entity).await.map_err(failed_at(step))?;
+                existing.insert(created.name);
+            }
+        }
+
+        if let Some(first) = spec.traits.first() {
+            let step = ProvisionStep::Trait(first.name.clone());
+            let mut existing: HashSet<String> = self
+                .get_traits()
+                .await
+                .map_err(failed_at(step))?
+                .into_iter()
+                .map(|existing_trait| existing_trait.name)
+                .collect();
+
+            for new_trait in spec.traits {
+                if existing.contains(&new_trait.name) {
+                    continue;
+                }
+                let step = ProvisionStep::Trait(new_trait.name.clone());
+                let created = self
+                    .create_trait(new_trait)
+                    .await
+                    .map_err(failed_at(step))?;
+                existing.insert(created.name);
+            }
+        }
+
+        if let Some(first) = spec.intents.first() {
+            let step = ProvisionStep::Intent(first.clone());
+            let mut existing: HashSet<String> = self
+                .get_intents()
+                .await
+                .map_err(failed_at(step))?
+                .into_iter()
+                .map(|intent| intent.name)
+                .collect();
+
+            for intent in spec.intents {
+                if existing.contains(&intent) {
+                    continue;
+                }
+                let step = ProvisionStep::Intent(intent.clone());
+                self.create_intent(&intent).await.map_err(failed_at(step))?;
+                existing.insert(intent);
+            }
+        }
+
+        if !spec.utterances.is_empty() {
+            self.create_utterances(spec.utterances)
+                .await
+                .map_err(failed_at(ProvisionStep::Utterances))?;
+        }
+
+        Ok(())
+    }
+}
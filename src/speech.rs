@@ -1,14 +1,24 @@
 //! Includes functionality related to sending speech requests to the wit api
 
-use crate::{client::WitClient, errors::Error, AudioType};
-use futures::{Stream, StreamExt};
+use crate::{
+    approx,
+    client::{self, WitClient},
+    dictation::DictationResponse,
+    errors::Error,
+    message::{self, MessageIntent, MessageOptions},
+    AudioType, Confidence,
+};
+use bytes::Bytes;
+use futures::{Stream, StreamExt, TryStreamExt};
 use reqwest::{
-    header::{CONTENT_TYPE, TRANSFER_ENCODING},
+    header::{CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT},
     Body,
 };
 use serde::Deserialize;
 use serde_json::Value;
-use std::{collections::HashMap, str::from_utf8};
+use std::{collections::HashMap, convert::Infallible, pin::Pin, str::from_utf8};
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
 
 /// A response chunk returned from the speech endpoint
 #[derive(Debug)]
@@ -39,21 +49,50 @@ pub struct UnderstandingResponse {
     pub traits: HashMap<String, Vec<UnderstandingTrait>>,
 }
 
-/// Information about an intent
-#[derive(Debug, Deserialize)]
-pub struct UnderstandingIntent {
-    /// The intent's id
-    pub id: String,
-    /// The intent's name
-    pub name: String,
-    /// The model's confidence in its detection of the intent
-    pub confidence: f64,
+/// Information about an intent. This has the same shape as an intent returned from the
+/// message endpoint
+pub type UnderstandingIntent = MessageIntent;
+
+impl UnderstandingResponse {
+    /// The intent wit is most confident in, if any intents were returned
+    pub fn top_intent(&self) -> Option<&UnderstandingIntent> {
+        message::top_intent(&self.intents)
+    }
+
+    /// The intent wit is most confident in, if its confidence is at least `threshold`
+    pub fn intent_above(&self, threshold: f64) -> Option<&UnderstandingIntent> {
+        message::intent_above(&self.intents, threshold)
+    }
+
+    /// The value of the trait with the given name that wit is most confident in. When `n` is
+    /// greater than 1, wit may return several candidate values for a trait (ex. both
+    /// `positive` and `neutral` for `wit$sentiment`), and all of them are kept in `traits`
+    pub fn top_trait(&self, name: &str) -> Option<&UnderstandingTrait> {
+        self.traits.get(name)?.iter().max_by(|a, b| {
+            a.confidence
+                .into_inner()
+                .total_cmp(&b.confidence.into_inner())
+        })
+    }
+
+    /// Whether this response matches `other`, allowing confidences to differ by up to
+    /// `epsilon` (see `MessageResponse::approx_eq`)
+    pub fn approx_eq(&self, other: &UnderstandingResponse, epsilon: f64) -> bool {
+        self.approx_diff(other, epsilon).is_empty()
+    }
+
+    /// Every difference between this response and `other` that `approx_eq` would reject
+    /// (see `MessageResponse::approx_diff`)
+    pub fn approx_diff(&self, other: &UnderstandingResponse, epsilon: f64) -> Vec<String> {
+        approx::understanding_differences(self, other, epsilon)
+    }
 }
 
 /// Information about an entity
 #[derive(Debug, Deserialize)]
 pub struct UnderstandingEntity {
     /// The entity's id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The entity's name
     pub name: String,
@@ -66,7 +105,7 @@ pub struct UnderstandingEntity {
     /// The body of the entity; what was found in the text
     pub body: String,
     /// The model's confidence in its detection of the entity
-    pub confidence: f64,
+    pub confidence: Confidence,
     /// The parsed value of the entity
     pub value: Value, // this might not exist???
     /// Further entities associated with this entity
@@ -77,21 +116,245 @@ pub struct UnderstandingEntity {
 #[derive(Debug, Deserialize)]
 pub struct UnderstandingTrait {
     /// The trait's id
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The value of the trait
     pub value: Value,
     /// The model's confidence in its detection of the trait
-    pub confidence: f64,
+    pub confidence: Confidence,
+}
+
+/// A boxed stream of speech results, as returned by `speech_boxed`. Unlike the `impl Stream`
+/// returned by `speech`, this type can be named, so it can be stored in a struct field
+pub type SpeechStream = Pin<Box<dyn Stream<Item = Result<SpeechResponse, Error>> + Send>>;
+
+/// A receiver for the results of a speech request, as an alternative to consuming the stream
+/// returned by `speech` directly. Each method is called as the corresponding result arrives.
+///
+/// This is implemented for closures that take a `Result<SpeechResponse, Error>`, so a closure
+/// can be used as a sink that handles every result in one place
+pub trait SpeechSink {
+    /// Called when a partial transcription arrives
+    fn on_transcription(&mut self, transcription: TranscriptionResponse);
+
+    /// Called when an understanding response arrives
+    fn on_understanding(&mut self, understanding: UnderstandingResponse);
+
+    /// Called when an error occurs while receiving or parsing results
+    fn on_error(&mut self, error: Error);
+}
+
+impl<F: FnMut(Result<SpeechResponse, Error>)> SpeechSink for F {
+    fn on_transcription(&mut self, transcription: TranscriptionResponse) {
+        self(Ok(SpeechResponse::Transcription(transcription)))
+    }
+
+    fn on_understanding(&mut self, understanding: UnderstandingResponse) {
+        self(Ok(SpeechResponse::Understanding(understanding)))
+    }
+
+    fn on_error(&mut self, error: Error) {
+        self(Err(error))
+    }
+}
+
+/// Tracks the state of a voice interaction as results arrive from the speech endpoint, so that
+/// the transcript can be rendered as it grows and the understanding used once it is final.
+///
+/// Wit sends the full text heard so far with each partial transcription, followed by an
+/// understanding once the utterance is complete. A transcription that arrives after an
+/// understanding starts a new utterance, and clears the previous understanding.
+///
+/// Example:
+/// ```rust,no_run
+/// # tokio_test::block_on(async {
+/// # use futures::StreamExt;
+/// # use wit_ai_rs::client::WitClient;
+/// # use wit_ai_rs::common_types::AudioType;
+/// # use wit_ai_rs::speech::SpeechSession;
+/// # let wit_client = WitClient::new(String::new(), String::new());
+/// # let file = tokio::fs::File::open("test.mp3").await.unwrap();
+/// let mut results = Box::pin(wit_client.speech(file, AudioType::MP3).await.unwrap());
+///
+/// let mut session = SpeechSession::new();
+///
+/// while let Some(result) = results.next().await {
+///     session.apply(result.unwrap());
+///
+///     let snapshot = session.snapshot();
+///
+///     println!("heard so far: {}", snapshot.current_text);
+///
+///     if let Some(understanding) = snapshot.final_understanding {
+///         println!("intents: {:?}", understanding.intents);
+///     }
+/// }
+/// # })
+/// ```
+#[derive(Debug, Default)]
+pub struct SpeechSession {
+    current_text: String,
+    final_understanding: Option<UnderstandingResponse>,
+}
+
+/// The state of a `SpeechSession` at a point in time
+#[derive(Debug)]
+pub struct SpeechSnapshot<'a> {
+    /// The transcript of the current utterance so far
+    pub current_text: &'a str,
+    /// The understanding of the current utterance, once it is complete
+    pub final_understanding: Option<&'a UnderstandingResponse>,
+}
+
+impl SpeechSession {
+    /// Create a new session, with no text and no understanding
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the session with the next result from the speech endpoint
+    pub fn apply(&mut self, response: SpeechResponse) {
+        match response {
+            SpeechResponse::Transcription(transcription) => {
+                self.final_understanding = None;
+                self.current_text = transcription.text;
+            }
+            SpeechResponse::Understanding(understanding) => {
+                self.current_text.clone_from(&understanding.text);
+                self.final_understanding = Some(understanding);
+            }
+        }
+    }
+
+    /// The current transcript and, if the utterance is complete, its understanding
+    pub fn snapshot(&self) -> SpeechSnapshot<'_> {
+        SpeechSnapshot {
+            current_text: &self.current_text,
+            final_understanding: self.final_understanding.as_ref(),
+        }
+    }
+}
+
+/// Whether a JSON object from the speech endpoint contains any fields that are only found
+/// in understanding responses
+fn has_understanding_fields(chunk: &[u8]) -> bool {
+    match serde_json::from_slice::<Value>(chunk) {
+        Ok(Value::Object(fields)) => ["intents", "entities", "traits"]
+            .iter()
+            .any(|field| fields.contains_key(*field)),
+        _ => false,
+    }
+}
+
+/// Parse the chunked body of a speech response into a stream of results, ending the stream
+/// with an error if a single result is larger than `max_object_size`
+fn parse_speech_response(
+    response: reqwest::Response,
+    max_object_size: Option<usize>,
+) -> impl Stream<Item = Result<SpeechResponse, Error>> + Send + 'static {
+    let stream = response.bytes_stream();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut too_large = false;
+
+    let stream_of_streams = stream.map(move |chunk_bytes| {
+            if too_large {
+                return futures::stream::iter(vec![]);
+            }
+
+            if let Err(err) = chunk_bytes {
+                return futures::stream::iter(vec![Err(Error::ResponseParseError(err))]);
+            }
+
+            let chunk_data =
+                chunk_bytes.expect("chunk_bytes should cause an early return if it is an error");
+
+            buffer.extend_from_slice(&chunk_data);
+
+            let mut speech_objs: Vec<Result<SpeechResponse, Error>> = Vec::new();
+            let mut start = 0;
+
+            // every JSON object ends with a carriage return,
+            // except for the last one
+            let json_obj_separator = b"\r\n";
+            let separator_length = json_obj_separator.len();
+
+            let mut parse_chunk = |chunk: &[u8]| {
+                let understanding_error =
+                    match serde_json::from_slice::<UnderstandingResponse>(chunk) {
+                        Ok(json_object) => {
+                            speech_objs.push(Ok(SpeechResponse::Understanding(json_object)));
+                            return;
+                        }
+                        Err(err) => err,
+                    };
+
+                if let Ok(transcription) = serde_json::from_slice::<TranscriptionResponse>(chunk) {
+                    // keep the transcribed text even if the rest of an understanding
+                    // response could not be parsed
+                    if has_understanding_fields(chunk) {
+                        log::warn!(
+                            "dropping understanding details that could not be parsed ({understanding_error}), \
+                            returning only the transcription: {:?}",
+                            transcription.text
+                        );
+                    }
+
+                    speech_objs.push(Ok(SpeechResponse::Transcription(transcription)));
+                } else if let Ok(response_str) = from_utf8(chunk) {
+                    speech_objs.push(Err(Error::JSONParseError(format!(
+                        "{response_str} could not be parsed into JSON"
+                    ))));
+                } else {
+                    speech_objs.push(Err(Error::JSONParseError(
+                        "response could not be parsed into utf8".to_string(),
+                    )))
+                }
+            };
+
+            while let Some(end) = buffer[start..]
+                .windows(separator_length)
+                .position(|w| w == json_obj_separator)
+            {
+                let json_chunk = &buffer[start..start + end + separator_length];
+                start += end + separator_length;
+
+                parse_chunk(json_chunk);
+            }
+
+            buffer.drain(..start);
+
+            let oversized = max_object_size.filter(|max_size| buffer.len() > *max_size);
+
+            // the very last JSON object does not end with a carriage return
+            if oversized.is_none() && buffer.ends_with(b"\n}") {
+                parse_chunk(&buffer);
+            }
+
+            if let Some(max_size) = oversized {
+                too_large = true;
+                buffer.clear();
+                speech_objs.push(Err(Error::ResponseTooLarge(max_size)));
+            }
+
+            // return the successfully deserialized JSON objects
+            futures::stream::iter(speech_objs)
+        });
+
+    stream_of_streams.flatten()
 }
 
 impl WitClient {
     /// Send a request to the speech endpoint, which takes in audio and returns both partial
     /// transcription and meaning extracted from the audio. Here, audio data is the audio data source
     /// (for example, a `tokio::fs::File``), and audio type is the type of audio (ex. mp3 or wav).
+    /// To track upload progress, wrap the audio data in a `ProgressBody`.
     ///
     /// Returns a result of a stream, and each item of this stream is a result where the Ok
     /// variant is an enum SpeechResponse, representing either a partial transcription or
-    /// a more detailed understanding response
+    /// a more detailed understanding response. If an understanding response arrives that cannot be
+    /// fully parsed, a warning is logged (using the `log` crate) and its text is still returned
+    /// as a transcription response
     ///
     /// Example:
     /// ```rust,no_run
@@ -128,84 +391,332 @@ impl WitClient {
         audio_data: impl Into<Body>,
         audio_type: AudioType,
     ) -> Result<impl Stream<Item = Result<SpeechResponse, Error>>, Error> {
-        let url = "https://api.wit.ai/speech?v=20230215";
+        let response = self
+            .send_speech_request(audio_data, audio_type, vec![], None)
+            .await?;
 
-        // internally, when a tokio::fs::File is passed to .body(), it is streamed with ReaderStream
-        // and wrap_stream()
+        Ok(parse_speech_response(response, self.max_response_size))
+    }
 
+    /// Send a request to the speech endpoint like `speech`, including options such as context
+    /// and dynamic entities, which are sent as URL parameters alongside the audio. This allows
+    /// stateful voice sessions, for example by sending the same context as text-based
+    /// message requests.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::message::{ContextBuilder, MessageOptionsBuilder};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let context = ContextBuilder::new()
+    ///     .timezone("America/Los_Angeles")
+    ///     .build();
+    ///
+    /// let options = MessageOptionsBuilder::new().context(context).build();
+    ///
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    ///
+    /// let result = wit_client
+    ///     .speech_with_options(file, AudioType::MP3, options)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn speech_with_options(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        options: MessageOptions,
+    ) -> Result<impl Stream<Item = Result<SpeechResponse, Error>>, Error> {
         let response = self
-            .reqwest_client
-            .post(url)
-            .bearer_auth(&self.auth_token)
-            .header(CONTENT_TYPE, audio_type.to_string())
-            .header(TRANSFER_ENCODING, "chunked") // DO I NEED THIS HEADER?
-            .body(audio_data)
-            .send()
+            .send_speech_request(audio_data, audio_type, options.get_url_params()?, None)
+            .await?;
+
+        Ok(parse_speech_response(response, self.max_response_size))
+    }
+
+    /// Send a request to the speech endpoint like `speech_with_options`, with the length of the
+    /// audio in bytes given up front so that it is sent as the `Content-Length` rather than
+    /// with chunked encoding (see `dictation_with_length`). The length is not detected from the
+    /// body, so callers must pass it, for a file by reading it from its metadata as below
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::message::MessageOptions;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    /// let length = file.metadata().await.unwrap().len();
+    ///
+    /// let result = wit_client
+    ///     .speech_with_length(file, AudioType::MP3, MessageOptions::default(), length)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn speech_with_length(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        options: MessageOptions,
+        content_length: u64,
+    ) -> Result<impl Stream<Item = Result<SpeechResponse, Error>>, Error> {
+        let response = self
+            .send_speech_request(
+                audio_data,
+                audio_type,
+                options.get_url_params()?,
+                Some(content_length),
+            )
             .await?;
 
-        let stream = response.bytes_stream();
+        Ok(parse_speech_response(response, self.max_response_size))
+    }
 
-        let mut buffer: Vec<u8> = Vec::new();
+    async fn send_speech_request(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        url_params: Vec<(String, String)>,
+        content_length: Option<u64>,
+    ) -> Result<reqwest::Response, Error> {
+        audio_type.warn_if_not_streamable();
 
-        let stream_of_streams = stream.map(move |chunk_bytes| {
-            if let Err(err) = chunk_bytes {
-                return futures::stream::iter(vec![Err(Error::ResponseParseError(err))]);
-            }
+        let url = format!(
+            "{}/speech?v={}",
+            self.api_host,
+            self.get_streaming_version()
+        );
 
-            let chunk_data =
-                chunk_bytes.expect("chunk_bytes should cause an early return if it is an error");
+        // internally, when a tokio::fs::File is passed to .body(), it is streamed with ReaderStream
+        // and wrap_stream(). `Transfer-Encoding: chunked` is not set here: hyper already uses it
+        // for bodies of unknown length and sends `Content-Length` for the rest, and setting it by
+        // hand sends both headers for bodies of known length, which some proxies reject
 
-            buffer.extend_from_slice(&chunk_data);
+        let _permit = self.acquire_request_permit().await;
 
-            let mut speech_objs: Vec<Result<SpeechResponse, Error>> = Vec::new();
-            let mut start = 0;
+        let mut request = self
+            .reqwest_client
+            .post(url)
+            .query(&url_params)
+            .bearer_auth(self.auth_token())
+            .header(USER_AGENT, &self.user_agent)
+            .header(CONTENT_TYPE, audio_type.to_string());
 
-            // every JSON object ends with a carriage return,
-            // except for the last one
-            let json_obj_separator = b"\r\n";
-            let separator_length = json_obj_separator.len();
+        // hyper sends a streamed body as-is, rather than chunked, when its length is given
+        if let Some(content_length) = content_length {
+            request = request.header(CONTENT_LENGTH, content_length);
+        }
 
-            let mut parse_chunk = |chunk: &[u8]| {
-                if let Ok(json_object) = serde_json::from_slice::<UnderstandingResponse>(chunk) {
-                    speech_objs.push(Ok(SpeechResponse::Understanding(json_object)));
-                } else if let Ok(transcription) =
-                    serde_json::from_slice::<TranscriptionResponse>(chunk)
-                {
-                    speech_objs.push(Ok(SpeechResponse::Transcription(transcription)));
-                } else if let Ok(response_str) = from_utf8(chunk) {
-                    speech_objs.push(Err(Error::JSONParseError(format!(
-                        "{response_str} could not be parsed into JSON"
-                    ))));
-                } else {
-                    speech_objs.push(Err(Error::JSONParseError(
-                        "response could not be parsed into utf8".to_string(),
-                    )))
-                }
-            };
+        let response = request.body(audio_data).send().await?;
 
-            while let Some(end) = buffer[start..]
-                .windows(separator_length)
-                .position(|w| w == json_obj_separator)
-            {
-                let json_chunk = &buffer[start..start + end + separator_length];
-                start += end + separator_length;
+        client::log_deprecation_notices(&response);
 
-                parse_chunk(json_chunk);
-            }
+        Ok(response)
+    }
 
-            buffer.drain(..start);
+    /// Send a request to the speech endpoint like `speech`, but instead of returning a stream,
+    /// pass each result to the corresponding method of `sink` as it arrives. Returns once all
+    /// results have been received, or with an error if the request could not be sent.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::speech::SpeechResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    ///
+    /// wit_client
+    ///     .speech_to_sink(file, AudioType::MP3, &mut |res| match res {
+    ///         Ok(SpeechResponse::Transcription(transcription)) => println!("{}", transcription.text),
+    ///         Ok(SpeechResponse::Understanding(understanding)) => println!("{:?}", understanding),
+    ///         Err(err) => eprintln!("{err}"),
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn speech_to_sink(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+        sink: &mut impl SpeechSink,
+    ) -> Result<(), Error> {
+        let mut speech = std::pin::pin!(self.speech(audio_data, audio_type).await?);
 
-            // the very last JSON object does not end with a carriage return
-            if buffer.ends_with(b"\n}") {
-                parse_chunk(&buffer);
+        while let Some(result) = speech.next().await {
+            match result {
+                Ok(SpeechResponse::Transcription(transcription)) => {
+                    sink.on_transcription(transcription)
+                }
+                Ok(SpeechResponse::Understanding(understanding)) => {
+                    sink.on_understanding(understanding)
+                }
+                Err(err) => sink.on_error(err),
             }
+        }
 
-            // return the successfully deserialized JSON objects
-            futures::stream::iter(speech_objs)
-        });
+        Ok(())
+    }
+
+    /// Send a request to the speech endpoint like `speech`, but return the results as a boxed,
+    /// nameable `SpeechStream` rather than an `impl Stream`. This is useful for holding the
+    /// stream in a struct, at the cost of a heap allocation and dynamic dispatch when polling
+    /// the stream--prefer `speech` when the stream does not need to be named.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::speech::SpeechStream;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// struct Session {
+    ///     results: SpeechStream,
+    /// }
+    ///
+    /// let file = tokio::fs::File::open("test.mp3").await.unwrap();
+    ///
+    /// let session = Session {
+    ///     results: wit_client.speech_boxed(file, AudioType::MP3).await.unwrap(),
+    /// };
+    /// # })
+    /// ```
+    pub async fn speech_boxed(
+        &self,
+        audio_data: impl Into<Body>,
+        audio_type: AudioType,
+    ) -> Result<SpeechStream, Error> {
+        let response = self
+            .send_speech_request(audio_data, audio_type, vec![], None)
+            .await?;
+
+        Ok(Box::pin(parse_speech_response(
+            response,
+            self.max_response_size,
+        )))
+    }
+
+    /// Send a request to the speech endpoint like `speech`, streaming the audio from anything that
+    /// implements `AsyncRead`, such as `tokio::io::stdin()`. This is useful for command-line tools
+    /// that have audio piped into them.
+    ///
+    /// Example (ex. `cat audio.mp3 | my_tool`):
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use futures::StreamExt;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let result = wit_client
+    ///     .speech_from_reader(tokio::io::stdin(), AudioType::MP3)
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// result.for_each(|res| async move { println!("{:?}", res) }).await;
+    /// # })
+    /// ```
+    pub async fn speech_from_reader(
+        &self,
+        reader: impl AsyncRead + Send + Sync + 'static,
+        audio_type: AudioType,
+    ) -> Result<impl Stream<Item = Result<SpeechResponse, Error>>, Error> {
+        self.speech(Body::wrap_stream(ReaderStream::new(reader)), audio_type)
+            .await
+    }
+
+    /// Send a request to the speech endpoint like `speech`, streaming the audio from a stream
+    /// of chunks. Any stream adapter can be placed in front of the request this way, so audio
+    /// can be preprocessed (ex. resampled or amplified) as it is sent, without this crate
+    /// knowing anything about the format. The chunks are sent as they are produced
+    ///
+    /// Example (doubling the volume of raw 16-bit little-endian audio):
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::{AudioType, RawAudioConfig};
+    /// # use bytes::Bytes;
+    /// # use futures::StreamExt;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// # let microphone = futures::stream::empty::<Bytes>();
+    /// fn gain(chunk: Bytes) -> Bytes {
+    ///     chunk
+    ///         .chunks_exact(2)
+    ///         .flat_map(|sample| {
+    ///             let sample = i16::from_le_bytes([sample[0], sample[1]]);
+    ///             sample.saturating_mul(2).to_le_bytes()
+    ///         })
+    ///         .collect()
+    /// }
+    ///
+    /// let result = wit_client
+    ///     .speech_stream(
+    ///         microphone.map(gain),
+    ///         AudioType::Raw(RawAudioConfig::default()),
+    ///     )
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn speech_stream(
+        &self,
+        audio: impl Stream<Item = Bytes> + Send + 'static,
+        audio_type: AudioType,
+    ) -> Result<impl Stream<Item = Result<SpeechResponse, Error>>, Error> {
+        let body = Body::wrap_stream(audio.map(Ok::<_, Infallible>));
+
+        self.speech(body, audio_type).await
+    }
+
+    /// Send the same audio to both the dictation and speech endpoints at once, returning every
+    /// dictation result and every speech result. Useful for comparing wit's plain transcription
+    /// with the transcription it produces while extracting meaning.
+    ///
+    /// Since the audio is sent twice, it must be held in memory rather than streamed. Fails with
+    /// the first error from either endpoint
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let audio = tokio::fs::read("test.mp3").await.unwrap();
+    ///
+    /// let (dictation, speech) = wit_client
+    ///     .transcribe_and_understand(audio, AudioType::MP3)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn transcribe_and_understand(
+        &self,
+        audio: Vec<u8>,
+        audio_type: AudioType,
+    ) -> Result<(Vec<DictationResponse>, Vec<SpeechResponse>), Error> {
+        // `Bytes` is reference counted, so the audio is not copied for the second request
+        let audio = Bytes::from(audio);
+
+        let dictation = async {
+            self.dictation(audio.clone(), audio_type)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await
+        };
 
-        let speech = stream_of_streams.flatten();
+        let speech = async {
+            self.speech(audio.clone(), audio_type)
+                .await?
+                .try_collect::<Vec<_>>()
+                .await
+        };
 
-        Ok(speech)
+        futures::try_join!(dictation, speech)
     }
 }
@@ -0,0 +1,164 @@
+//! Includes a method and types related to converting text to speech with the wit api
+
+use crate::{
+    client::WitClient,
+    common_types::{AudioType, RawAudioConfig},
+    errors::Error,
+};
+use reqwest::{header::CONTENT_TYPE, Method};
+use serde::Serialize;
+
+/// A request to synthesize speech, built with `SynthesizeRequestBuilder`
+#[derive(Debug, Clone, Serialize)]
+pub struct SynthesizeRequest {
+    q: String,
+    voice: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    style: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pitch: Option<u16>,
+    #[serde(skip)]
+    output_format: AudioType,
+}
+
+impl SynthesizeRequest {
+    /// The format of the audio that will be returned for this request
+    pub fn output_format(&self) -> AudioType {
+        self.output_format
+    }
+}
+
+/// Audio synthesized by `WitClient::synthesize`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SynthesizeResponse {
+    /// The audio itself
+    pub audio: Vec<u8>,
+    /// The format of the audio, as given by the response's `Content-Type`. This is the
+    /// requested output format unless wit chose a different one
+    pub audio_type: AudioType,
+}
+
+/// Builder for `SynthesizeRequest`
+#[derive(Debug, Clone)]
+pub struct SynthesizeRequestBuilder {
+    request: SynthesizeRequest,
+}
+
+impl SynthesizeRequestBuilder {
+    /// Create a builder for a request to speak `text` with the given voice (ex. `Rebecca`).
+    /// By default, the audio is returned as MP3
+    pub fn new(text: impl Into<String>, voice: impl Into<String>) -> Self {
+        Self {
+            request: SynthesizeRequest {
+                q: text.into(),
+                voice: voice.into(),
+                style: None,
+                speed: None,
+                pitch: None,
+                output_format: AudioType::MP3,
+            },
+        }
+    }
+
+    /// The style of the voice (ex. `soft` or `formal`), which depends on the voice used
+    pub fn style(mut self, style: impl Into<String>) -> Self {
+        self.request.style = Some(style.into());
+        self
+    }
+
+    /// The speed of the speech, as a percentage of the voice's default speed
+    pub fn speed(mut self, speed: u16) -> Self {
+        self.request.speed = Some(speed);
+        self
+    }
+
+    /// The pitch of the speech, as a percentage of the voice's default pitch
+    pub fn pitch(mut self, pitch: u16) -> Self {
+        self.request.pitch = Some(pitch);
+        self
+    }
+
+    /// The format the audio should be returned in, which is requested with the `Accept`
+    /// header. Wit can produce MP3, WAV, and raw 16-bit signed little-endian PCM at 16kHz
+    /// (the default `RawAudioConfig`)--any other format results in an `InvalidArgument` error
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::{common_types::AudioType, synthesize::SynthesizeRequestBuilder};
+    /// let request = SynthesizeRequestBuilder::new("hello world", "Rebecca")
+    ///     .output_format(AudioType::WAV)
+    ///     .unwrap()
+    ///     .build();
+    /// ```
+    pub fn output_format(mut self, output_format: AudioType) -> Result<Self, Error> {
+        accept_header(output_format)?;
+
+        self.request.output_format = output_format;
+        Ok(self)
+    }
+
+    /// Transform the `SynthesizeRequestBuilder` into a `SynthesizeRequest`
+    pub fn build(self) -> SynthesizeRequest {
+        self.request
+    }
+}
+
+/// The `Accept` header that asks wit for audio in the given format, if it can produce it
+fn accept_header(output_format: AudioType) -> Result<&'static str, Error> {
+    match output_format {
+        AudioType::MP3 => Ok("audio/mpeg"),
+        AudioType::WAV => Ok("audio/wav"),
+        AudioType::Raw(config) if config == RawAudioConfig::default() => Ok("audio/pcm16"),
+        AudioType::Raw(_) => Err(Error::InvalidArgument(format!(
+            "wit cannot synthesize {output_format}, only raw audio in the default RawAudioConfig"
+        ))),
+    }
+}
+
+impl WitClient {
+    /// Convert text to speech, returning the audio along with the format wit sent it in (see
+    /// `SynthesizeRequestBuilder::output_format`). If the response has no `Content-Type`, or
+    /// one that is not a known audio type, the requested output format is assumed
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::common_types::AudioType;
+    /// # use wit_ai_rs::synthesize::{SynthesizeRequestBuilder, SynthesizeResponse};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let request = SynthesizeRequestBuilder::new("hello world", "Rebecca").build();
+    ///
+    /// let response: SynthesizeResponse = wit_client.synthesize(request).await.unwrap();
+    ///
+    /// let extension = match response.audio_type {
+    ///     AudioType::MP3 => "mp3",
+    ///     AudioType::WAV => "wav",
+    ///     AudioType::Raw(_) => "raw",
+    /// };
+    ///
+    /// std::fs::write(format!("output.{extension}"), response.audio).unwrap();
+    /// # })
+    /// ```
+    pub async fn synthesize(
+        &self,
+        request: SynthesizeRequest,
+    ) -> Result<SynthesizeResponse, Error> {
+        let requested_type = request.output_format;
+        let accept = accept_header(requested_type)?;
+
+        let (audio, headers) = self
+            .make_binary_request(Method::POST, "/synthesize", vec![], Some(request), accept)
+            .await?;
+
+        let audio_type = headers
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .and_then(|content_type| content_type.parse().ok())
+            .unwrap_or(requested_type);
+
+        Ok(SynthesizeResponse { audio, audio_type })
+    }
+}
@@ -1,25 +1,35 @@
 //! Interacting with Wit traits
 
 use crate::{
-    client::WitClient,
+    client::{encode_path, WitClient},
     common_types::{DeleteResponse, TraitBasic},
     errors::Error,
 };
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
+use std::collections::HashSet;
 
 /// Struct to use for creating a new trait
 #[derive(Debug, Serialize)]
 pub struct NewTrait {
-    name: String,
+    pub(crate) name: String,
     values: Vec<String>,
 }
 
 impl NewTrait {
     /// Constructor for `NewTrait`
-    pub fn new(name: String, values: Vec<String>) -> Self {
-        Self { name, values }
+    pub fn new(name: impl Into<String>, values: Vec<String>) -> Self {
+        Self {
+            name: name.into(),
+            values,
+        }
+    }
+
+    /// The JSON body that `create_trait` sends for this trait, for checking it against wit's
+    /// documentation
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("should be able to serialize NewTrait")
     }
 }
 
@@ -27,6 +37,7 @@ impl NewTrait {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct TraitResponse {
     /// The id of the trait
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the trait
     pub name: String,
@@ -38,6 +49,7 @@ pub struct TraitResponse {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct TraitValue {
     /// The id of the value
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The value itself
     pub value: String,
@@ -46,6 +58,9 @@ pub struct TraitValue {
 impl WitClient {
     /// Get all the traits from app associated with the current wit client
     ///
+    /// This endpoint is not paged by wit, so the response always contains every trait (as
+    /// a name and id only--see `get_trait` for a trait's values).
+    ///
     /// Example:
     /// ```rust,no_run
     /// # tokio_test::block_on(async {
@@ -71,7 +86,7 @@ impl WitClient {
     /// # use wit_ai_rs::client::WitClient;
     /// # use wit_ai_rs::traits::{TraitResponse, NewTrait};
     /// # let wit_client = WitClient::new(String::new(), String::new());
-    /// let new_trait = NewTrait::new("trait_name".to_string(), vec!["value1".to_string()]);
+    /// let new_trait = NewTrait::new("trait_name", vec!["value1".to_string()]);
     ///
     /// let response: TraitResponse = wit_client.create_trait(new_trait).await.unwrap();
     /// # })
@@ -84,6 +99,34 @@ impl WitClient {
         Ok(data)
     }
 
+    /// Create a new trait if no trait with the same name exists, otherwise return the existing
+    /// trait unchanged. Unlike `create_trait`, this can safely be called repeatedly
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::traits::{TraitResponse, NewTrait};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let new_trait = NewTrait::new("trait_name", vec!["value1".to_string()]);
+    ///
+    /// let response: TraitResponse = wit_client.ensure_trait(new_trait).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn ensure_trait(&self, new_trait: NewTrait) -> Result<TraitResponse, Error> {
+        let exists = self
+            .get_traits()
+            .await?
+            .iter()
+            .any(|existing| existing.name == new_trait.name);
+
+        if exists {
+            return self.get_trait(&new_trait.name).await;
+        }
+
+        self.create_trait(new_trait).await
+    }
+
     /// Get information about a given trait
     ///
     /// Example:
@@ -95,16 +138,150 @@ impl WitClient {
     /// let response: TraitResponse = wit_client.get_trait("intent_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn get_trait(&self, trait_name: &str) -> Result<TraitResponse, Error> {
+    pub async fn get_trait(&self, trait_name: impl AsRef<str>) -> Result<TraitResponse, Error> {
+        let trait_name = trait_name.as_ref();
         let endpoint = format!("/traits/{trait_name}");
 
         let data = self
             .make_request(Method::GET, &endpoint, vec![], Option::<Value>::None)
-            .await?;
+            .await
+            .map_err(|err| {
+                err.map_not_found(|source| Error::TraitNotFound(trait_name.to_owned(), source))
+            })?;
 
         Ok(data)
     }
 
+    /// Add a value to an existing trait, returning the trait with its new value
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::traits::TraitResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: TraitResponse = wit_client
+    ///     .add_trait_value("politeness", "neutral")
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn add_trait_value(
+        &self,
+        trait_name: impl AsRef<str>,
+        value: impl Into<String>,
+    ) -> Result<TraitResponse, Error> {
+        let trait_name = trait_name.as_ref();
+        let endpoint = format!("/traits/{trait_name}/values");
+
+        let data = self
+            .make_request(
+                Method::POST,
+                &endpoint,
+                vec![],
+                Some(json!({ "value": value.into() })),
+            )
+            .await
+            .map_err(|err| {
+                err.map_not_found(|source| Error::TraitNotFound(trait_name.to_owned(), source))
+            })?;
+
+        Ok(data)
+    }
+
+    /// Remove a value from a trait. Utterances that used the value lose their trait
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::DeleteResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: DeleteResponse = wit_client
+    ///     .delete_trait_value("politeness", "rude")
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn delete_trait_value(
+        &self,
+        trait_name: impl AsRef<str>,
+        value: impl AsRef<str>,
+    ) -> Result<DeleteResponse, Error> {
+        let trait_name = trait_name.as_ref();
+
+        // values are free text, so they may contain characters with a meaning in URLs
+        let endpoint = encode_path(&["traits", trait_name, "values", value.as_ref()]);
+
+        let data = self
+            .make_request(Method::DELETE, &endpoint, vec![], Option::<Value>::None)
+            .await
+            .map_err(|err| {
+                err.map_not_found(|source| Error::TraitNotFound(trait_name.to_owned(), source))
+            })?;
+
+        Ok(data)
+    }
+
+    /// Change the values of an existing trait to exactly `values`, by adding the values it is
+    /// missing and deleting the ones that are not listed. Wit cannot update a trait in place,
+    /// but unlike deleting and recreating it, this keeps the trait (and the values that did not
+    /// change) attached to existing utterances. A value listed more than once is only added
+    /// once. Returns the trait as it is after the update.
+    ///
+    /// Intents have no equivalent, since an intent only has a name, which wit cannot change
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::traits::TraitResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let values = vec!["polite".to_string(), "neutral".to_string()];
+    ///
+    /// let response: TraitResponse = wit_client
+    ///     .update_trait_values("politeness", values)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn update_trait_values(
+        &self,
+        trait_name: impl AsRef<str>,
+        values: Vec<String>,
+    ) -> Result<TraitResponse, Error> {
+        let trait_name = trait_name.as_ref();
+
+        // a repeated value would otherwise be added twice, and the second add would fail after
+        // the first had already changed the trait
+        let mut seen = HashSet::new();
+        let values: Vec<&str> = values
+            .iter()
+            .map(String::as_str)
+            .filter(|value| seen.insert(*value))
+            .collect();
+
+        let current = self.get_trait(trait_name).await?;
+
+        for &value in &values {
+            if !current
+                .values
+                .iter()
+                .any(|existing| existing.value == value)
+            {
+                self.add_trait_value(trait_name, value).await?;
+            }
+        }
+
+        for existing in &current.values {
+            if !values.contains(&existing.value.as_str()) {
+                self.delete_trait_value(trait_name, &existing.value).await?;
+            }
+        }
+
+        self.get_trait(trait_name).await
+    }
+
     /// Delete a trait by name
     ///
     /// Example:
@@ -116,8 +293,8 @@ impl WitClient {
     /// let response: DeleteResponse = wit_client.delete_trait("intent_name").await.unwrap();
     /// # })
     /// ```
-    pub async fn delete_trait(&self, trait_name: &str) -> Result<DeleteResponse, Error> {
-        let endpoint = format!("/traits/{trait_name}");
+    pub async fn delete_trait(&self, trait_name: impl AsRef<str>) -> Result<DeleteResponse, Error> {
+        let endpoint = format!("/traits/{}", trait_name.as_ref());
 
         let data = self
             .make_request(Method::DELETE, &endpoint, vec![], Option::<Value>::None)
@@ -1,9 +1,24 @@
 //! Interacting with wit utterances
 
-use crate::{client::WitClient, errors::Error, IntentBasic};
+use crate::{
+    client::WitClient, errors::Error, json_stream::json_array_stream, message::MessageResponse,
+    IntentBasic,
+};
+use futures::{Stream, StreamExt, TryStreamExt};
 use reqwest::Method;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// The maximum number of utterances that can be fetched in a single request
+pub const MAX_UTTERANCES_LIMIT: u32 = 10000;
+
+/// The number of utterances fetched by a default `GetUtterancesRequestBuilder`
+pub const DEFAULT_UTTERANCES_LIMIT: u32 = 100;
 
 /// A request for getting information about all utterances
 #[derive(Debug)]
@@ -23,18 +38,27 @@ impl GetUtterancesRequestBuilder {
     /// Creates a new builder for `GetUtterancesRequest`, with the given limit value, which is the
     /// maximum number of utterances to return, between 1 and 10000 inclusive
     pub fn new(limit: u32) -> Result<Self, Error> {
-        if !(1..=10000).contains(&limit) {
+        if !(1..=MAX_UTTERANCES_LIMIT).contains(&limit) {
             return Err(Error::InvalidArgument(format!(
-                "limit for getting utterances must be between 1 and 10000 inclusive, got {}",
-                limit
+                "limit for getting utterances must be between 1 and {MAX_UTTERANCES_LIMIT} inclusive, got {limit}"
             )));
         }
 
-        Ok(Self {
+        Ok(Self::with_limit(limit))
+    }
+
+    /// Creates a new builder for `GetUtterancesRequest` that fetches the maximum number of
+    /// utterances allowed in one request (`MAX_UTTERANCES_LIMIT`)
+    pub fn max() -> Self {
+        Self::with_limit(MAX_UTTERANCES_LIMIT)
+    }
+
+    fn with_limit(limit: u32) -> Self {
+        Self {
             limit,
             offset: None,
             intents: None,
-        })
+        }
     }
 
     /// Number of utterances to skip (default is 0)
@@ -67,13 +91,22 @@ impl GetUtterancesRequestBuilder {
     }
 }
 
+impl Default for GetUtterancesRequestBuilder {
+    /// Default constructor for GetUtterancesRequestBuilder that fetches `DEFAULT_UTTERANCES_LIMIT`
+    /// utterances, with no offset or intent filter
+    fn default() -> Self {
+        Self::with_limit(DEFAULT_UTTERANCES_LIMIT)
+    }
+}
+
 /// Struct for associating an entity with a new utterace
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewUtteranceEntity {
     entity: String,
     start: u32,
     end: u32,
     body: String,
+    #[serde(default)]
     entities: Vec<NewUtteranceEntity>,
 }
 
@@ -85,24 +118,24 @@ impl NewUtteranceEntity {
     /// * `body` - the value of the entity as it appears in the text
     /// * `entity` - other entities within this entity
     pub fn new(
-        entity: String,
+        entity: impl Into<String>,
         start: u32,
         end: u32,
-        body: String,
+        body: impl Into<String>,
         entities: Vec<NewUtteranceEntity>,
     ) -> Self {
         Self {
-            entity,
+            entity: entity.into(),
             start,
             end,
-            body,
+            body: body.into(),
             entities,
         }
     }
 }
 
 /// Struct for associating a trait with a new utternace
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewUtteranceTrait {
     #[serde(rename = "trait")]
     trait_: String,
@@ -111,20 +144,24 @@ pub struct NewUtteranceTrait {
 
 impl NewUtteranceTrait {
     /// Constructor for `NewUtteranceTrait`
-    pub fn new(trait_name: String, value: String) -> Self {
+    pub fn new(trait_name: impl Into<String>, value: impl Into<String>) -> Self {
         Self {
-            trait_: trait_name,
-            value,
+            trait_: trait_name.into(),
+            value: value.into(),
         }
     }
 }
 
-/// Struct for creating a new utterance
-#[derive(Debug, Serialize)]
+/// Struct for creating a new utterance. It deserializes from the same JSON that wit accepts
+/// when creating utterances, except that `entities` and `traits` may be left out when empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewUtterance {
     text: String,
+    #[serde(default)]
     entities: Vec<NewUtteranceEntity>,
+    #[serde(default)]
     traits: Vec<NewUtteranceTrait>,
+    #[serde(default)]
     intent: Option<String>,
 }
 
@@ -135,18 +172,38 @@ impl NewUtterance {
     /// * `traits` - vector of traits associated with the utterance--must be preexisting traits (empty if no traits)
     /// * `intent` - the intent associated with the utterance--None if the intent is out of scope
     pub fn new(
-        text: String,
+        text: impl Into<String>,
         entities: Vec<NewUtteranceEntity>,
         traits: Vec<NewUtteranceTrait>,
         intent: Option<String>,
     ) -> Self {
         Self {
-            text,
+            text: text.into(),
             entities,
             traits,
             intent,
         }
     }
+
+    /// The JSON that `create_utterances` sends for this utterance, as one element of the
+    /// request body's array. Useful for checking a complex utterance against wit's
+    /// documentation before sending it
+    ///
+    /// Example:
+    /// ```rust
+    /// # use wit_ai_rs::utterances::{NewUtterance, NewUtteranceTrait};
+    /// let utterance = NewUtterance::new(
+    ///     "that was great",
+    ///     vec![],
+    ///     vec![NewUtteranceTrait::new("sentiment", "positive")],
+    ///     None,
+    /// );
+    ///
+    /// println!("{}", utterance.to_json());
+    /// ```
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).expect("should be able to serialize NewUtterance")
+    }
 }
 
 /// Response to a request to create an utterance
@@ -158,6 +215,24 @@ pub struct CreateUtteranceResponse {
     pub n: u32,
 }
 
+/// An utterance that was sent to wit but could not be found after creation
+#[derive(Debug, PartialEq)]
+pub struct FailedUtterance {
+    /// The text of the utterance
+    pub text: String,
+    /// The intent the utterance was sent with, if any
+    pub intent: Option<String>,
+}
+
+/// The result of creating utterances and then verifying that they exist
+#[derive(Debug, PartialEq)]
+pub struct VerifiedCreateUtterancesResponse {
+    /// The number of utterances wit reported as created
+    pub n: u32,
+    /// Utterances that were sent but could not be found afterwards
+    pub failed: Vec<FailedUtterance>,
+}
+
 /// Response to a request to delete an utterance
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct DeleteUtteranceResponse {
@@ -172,8 +247,8 @@ pub struct DeleteUtteranceResponse {
 pub struct UtteranceResponse {
     /// The text of the utterance
     pub text: String,
-    /// An intent associated with the utterance
-    pub intent: IntentBasic,
+    /// The intent associated with the utterance--None if the utterance is out of scope
+    pub intent: Option<IntentBasic>,
     /// Entities associated with the utterance
     pub entities: Vec<UtteranceResponseEntity>,
     /// Traits associated with the utterance
@@ -184,6 +259,7 @@ pub struct UtteranceResponse {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct UtteranceResponseEntity {
     /// The id of the entity
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the entity
     pub name: String,
@@ -203,6 +279,7 @@ pub struct UtteranceResponseEntity {
 #[derive(Debug, Deserialize, PartialEq)]
 pub struct UtteranceResponseTrait {
     /// The id of the trait
+    #[serde(deserialize_with = "crate::deserialize::string_or_number")]
     pub id: String,
     /// The name of the trait
     pub name: String,
@@ -210,6 +287,36 @@ pub struct UtteranceResponseTrait {
     pub value: String,
 }
 
+/// An intent, entity, or trait referenced by an utterance, as reported by
+/// `WitClient::validate_utterances`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UtteranceReference {
+    /// The name of an intent
+    Intent(String),
+    /// The name of an entity
+    Entity(String),
+    /// The name of a trait
+    Trait(String),
+}
+
+/// An utterance that references an intent, entity, or trait which does not exist in the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// The text of the utterance
+    pub text: String,
+    /// The reference that could not be found
+    pub missing: UtteranceReference,
+}
+
+/// A page of utterances produced by `WitClient::export_utterances_from`
+#[derive(Debug, PartialEq)]
+pub struct UtterancePage {
+    /// The utterances in this page
+    pub utterances: Vec<UtteranceResponse>,
+    /// The offset to pass to `export_utterances_from` to resume the export after this page
+    pub next_offset: u32,
+}
+
 impl WitClient {
     /// Return information about all utterances associated with the given app
     ///
@@ -244,6 +351,98 @@ impl WitClient {
         Ok(data)
     }
 
+    /// Like `get_utterances`, but returns each utterance as soon as it has been read from the
+    /// response, rather than buffering the whole response and deserializing it at once. This
+    /// keeps memory use bounded when fetching many utterances (up to `MAX_UTTERANCES_LIMIT`).
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use futures::StreamExt;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::utterances::GetUtterancesRequestBuilder;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let request = GetUtterancesRequestBuilder::max().build();
+    ///
+    /// let mut utterances = Box::pin(wit_client.get_utterances_stream(request));
+    ///
+    /// while let Some(utterance) = utterances.next().await {
+    ///     println!("{}", utterance.unwrap().text);
+    /// }
+    /// # })
+    /// ```
+    pub fn get_utterances_stream(
+        &self,
+        utterances_request: GetUtterancesRequest,
+    ) -> impl Stream<Item = Result<UtteranceResponse, Error>> + '_ {
+        let response =
+            self.make_streaming_request(Method::GET, "/utterances", utterances_request.url_params);
+
+        futures::stream::once(response)
+            .map(|response| match response {
+                Ok(response) => {
+                    json_array_stream(response.bytes_stream(), self.max_response_size).left_stream()
+                }
+                Err(err) => futures::stream::iter([Err(err)]).right_stream(),
+            })
+            .flatten()
+    }
+
+    /// Export all utterances associated with the given app, starting at `offset` (use 0 to
+    /// export from the beginning). Utterances are fetched in pages of `MAX_UTTERANCES_LIMIT`,
+    /// and the stream ends after the first page that is not full.
+    ///
+    /// Each page includes the offset at which the export continues, which can be stored by
+    /// the caller so that an interrupted export can be resumed by passing it back into this
+    /// method. Nothing is persisted by this method itself. Dropping the stream between pages
+    /// does not lose any utterances that come before the last reported offset.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use futures::TryStreamExt;
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// # let saved_offset = 0;
+    /// let mut pages = Box::pin(wit_client.export_utterances_from(saved_offset));
+    ///
+    /// while let Some(page) = pages.try_next().await.unwrap() {
+    ///     for utterance in page.utterances {
+    ///         println!("{}", utterance.text);
+    ///     }
+    ///
+    ///     // store page.next_offset somewhere durable to resume from later
+    /// }
+    /// # })
+    /// ```
+    pub fn export_utterances_from(
+        &self,
+        offset: u32,
+    ) -> impl Stream<Item = Result<UtterancePage, Error>> + '_ {
+        futures::stream::try_unfold(Some(offset), move |offset| async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+
+            let request = GetUtterancesRequestBuilder::max().offset(offset).build();
+
+            let utterances = self.get_utterances(request).await?;
+
+            let next_offset = offset + utterances.len() as u32;
+
+            // a page that is not full means there are no more utterances
+            let next = (utterances.len() == MAX_UTTERANCES_LIMIT as usize).then_some(next_offset);
+
+            Ok(Some((
+                UtterancePage {
+                    utterances,
+                    next_offset,
+                },
+                next,
+            )))
+        })
+    }
+
     /// Create new utterances for the given app
     ///
     /// Example:
@@ -255,17 +454,17 @@ impl WitClient {
     /// # };
     /// # let wit_client = WitClient::new(String::new(), String::new());
     /// let utterance_entity = NewUtteranceEntity::new(
-    ///     "entity:entity".to_string(),
+    ///     "entity:entity",
     ///     3,
     ///     12,
-    ///     "utterance".to_string(),
+    ///     "utterance",
     ///     vec![],
     /// );
     ///
-    /// let utterance_trait = NewUtteranceTrait::new("trait_name".to_string(), "value1".to_string());
+    /// let utterance_trait = NewUtteranceTrait::new("trait_name", "value1");
     ///
     /// let new_utterance = NewUtterance::new(
-    ///     "an utterance".to_string(),
+    ///     "an utterance",
     ///     vec![utterance_entity],
     ///     vec![utterance_trait],
     ///     Some("intent_name".to_string()),
@@ -288,6 +487,249 @@ impl WitClient {
         Ok(data)
     }
 
+    /// Create new utterances like `create_utterances`, but send them in batches of at most
+    /// `batch_size` utterances, one request at a time. The returned response combines the
+    /// responses for all batches. If a batch fails, the error is returned and no further batches
+    /// are sent, though earlier batches will already have been created.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::utterances::{NewUtterance, CreateUtteranceResponse};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let new_utterances = (0..1000)
+    ///     .map(|index| NewUtterance::new(format!("utterance {index}"), vec![], vec![], None))
+    ///     .collect();
+    ///
+    /// let response: CreateUtteranceResponse = wit_client
+    ///     .create_utterances_batched(new_utterances, 100)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn create_utterances_batched(
+        &self,
+        utterances: Vec<NewUtterance>,
+        batch_size: usize,
+    ) -> Result<CreateUtteranceResponse, Error> {
+        if batch_size == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "batch size for creating utterances must be at least 1",
+            )));
+        }
+
+        let mut combined = CreateUtteranceResponse { sent: true, n: 0 };
+
+        let mut utterances = utterances.into_iter().peekable();
+
+        while utterances.peek().is_some() {
+            let batch: Vec<NewUtterance> = utterances.by_ref().take(batch_size).collect();
+
+            let response = self.create_utterances(batch).await?;
+
+            combined.sent &= response.sent;
+            combined.n += response.n;
+        }
+
+        Ok(combined)
+    }
+
+    /// Create utterances read from newline-delimited JSON, where each line is one utterance in
+    /// the format wit accepts when creating utterances (see `NewUtterance`). Blank lines are
+    /// skipped. Utterances are uploaded in batches of at most `batch_size` as they are read, so
+    /// the whole input is never held in memory, and the returned response combines the
+    /// responses for all batches.
+    ///
+    /// A line that is not a valid utterance results in a `JSONParseError` naming its line
+    /// number (starting at 1), and an error reading the input in an `IoError`. Either way, no
+    /// further batches are sent, though earlier batches will already have been created
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::utterances::CreateUtteranceResponse;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let file = tokio::fs::File::open("utterances.ndjson").await.unwrap();
+    ///
+    /// let response: CreateUtteranceResponse = wit_client
+    ///     .import_utterances_ndjson(tokio::io::BufReader::new(file), 100)
+    ///     .await
+    ///     .unwrap();
+    /// # })
+    /// ```
+    pub async fn import_utterances_ndjson(
+        &self,
+        reader: impl AsyncBufRead + Unpin,
+        batch_size: usize,
+    ) -> Result<CreateUtteranceResponse, Error> {
+        if batch_size == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "batch size for creating utterances must be at least 1",
+            )));
+        }
+
+        let mut combined = CreateUtteranceResponse { sent: true, n: 0 };
+
+        let mut lines = reader.lines();
+        let mut line_number = 0;
+        let mut batch = Vec::with_capacity(batch_size);
+
+        loop {
+            let line = lines.next_line().await?;
+
+            if let Some(line) = &line {
+                line_number += 1;
+
+                if !line.trim().is_empty() {
+                    let utterance = serde_json::from_str(line).map_err(|err| {
+                        Error::JSONParseError(format!("line {line_number}: {err}"))
+                    })?;
+
+                    batch.push(utterance);
+                }
+            }
+
+            if batch.len() == batch_size || (line.is_none() && !batch.is_empty()) {
+                let response = self.create_utterances(std::mem::take(&mut batch)).await?;
+
+                combined.sent &= response.sent;
+                combined.n += response.n;
+            }
+
+            if line.is_none() {
+                return Ok(combined);
+            }
+        }
+    }
+
+    /// Create new utterances like `create_utterances`, then fetch the app's utterances to verify
+    /// that each one was created. Wit only reports the number of utterances created, so this is
+    /// the only way to find out which ones failed.
+    ///
+    /// Note that wit may process new utterances asynchronously, so an utterance that is still
+    /// being processed may be reported as failed. Fetching is done with the maximum limit and
+    /// repeated until all (relevant) utterances have been checked, which may take several requests
+    /// for large apps.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::utterances::{NewUtterance, VerifiedCreateUtterancesResponse};
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let new_utterance = NewUtterance::new(
+    ///     "an utterance",
+    ///     vec![],
+    ///     vec![],
+    ///     Some("intent_name".to_string()),
+    /// );
+    ///
+    /// let response: VerifiedCreateUtterancesResponse = wit_client
+    ///     .create_utterances_verified(vec![new_utterance])
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// for failed in response.failed {
+    ///     println!("failed to create: {}", failed.text);
+    /// }
+    /// # })
+    /// ```
+    pub async fn create_utterances_verified(
+        &self,
+        utterances: Vec<NewUtterance>,
+    ) -> Result<VerifiedCreateUtterancesResponse, Error> {
+        let mut pending: Vec<FailedUtterance> = utterances
+            .iter()
+            .map(|utterance| FailedUtterance {
+                text: utterance.text.clone(),
+                intent: utterance.intent.clone(),
+            })
+            .collect();
+
+        // only fetch utterances with the relevant intents, unless some utterances have no intent
+        let intents: Option<Vec<String>> = pending
+            .iter()
+            .map(|utterance| utterance.intent.clone())
+            .collect::<Option<Vec<String>>>()
+            .map(|mut intents| {
+                intents.sort();
+                intents.dedup();
+                intents
+            });
+
+        let created = self.create_utterances(utterances).await?;
+
+        let mut offset = 0;
+
+        while !pending.is_empty() {
+            let mut request = GetUtterancesRequestBuilder::max().offset(offset);
+
+            if let Some(intents) = &intents {
+                request = request.intents(intents.clone());
+            }
+
+            let existing = self.get_utterances(request.build()).await?;
+
+            pending.retain(|utterance| !existing.iter().any(|other| other.text == utterance.text));
+
+            if existing.len() < MAX_UTTERANCES_LIMIT as usize {
+                break;
+            }
+
+            offset += MAX_UTTERANCES_LIMIT;
+        }
+
+        Ok(VerifiedCreateUtterancesResponse {
+            n: created.n,
+            failed: pending,
+        })
+    }
+
+    /// Teach the app the correct interpretation of a message it got wrong, for example after a
+    /// user corrects the app's behavior. Wit has no separate feedback endpoint--corrections are
+    /// submitted as training utterances, so this is a thin wrapper around `create_utterances`
+    /// that first checks that the correction is for the same text as the original message
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # use wit_ai_rs::message::{MessageOptions, MessageResponse};
+    /// # use wit_ai_rs::utterances::NewUtterance;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// let response: MessageResponse = wit_client
+    ///     .message("turn it up", MessageOptions::default())
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// // the user indicated that the app misunderstood them
+    /// let corrected = NewUtterance::new(
+    ///     response.text.clone(),
+    ///     vec![],
+    ///     vec![],
+    ///     Some("increase_volume".to_string()),
+    /// );
+    ///
+    /// wit_client.submit_correction(&response, corrected).await.unwrap();
+    /// # })
+    /// ```
+    pub async fn submit_correction(
+        &self,
+        response: &MessageResponse,
+        corrected: NewUtterance,
+    ) -> Result<CreateUtteranceResponse, Error> {
+        if corrected.text != response.text {
+            return Err(Error::InvalidArgument(format!(
+                "correction is for \"{}\", but the message was \"{}\"",
+                corrected.text, response.text
+            )));
+        }
+
+        self.create_utterances(vec![corrected]).await
+    }
+
     /// Delete utterances
     /// * `utterance_texts` - a vector of strings, where each string is the text of an utterance to delete
     ///
@@ -318,4 +760,202 @@ impl WitClient {
 
         Ok(data)
     }
+
+    /// Check every utterance in the app for references to intents, entities, or traits that do
+    /// not exist (for example, because they were deleted), which would cause training to fail.
+    /// Returns an issue for each dangling reference, in the order the utterances were
+    /// exported--an empty list means every reference was found.
+    ///
+    /// Built-in intents, entities, and traits (those starting with `wit$`) are always
+    /// considered to exist.
+    ///
+    /// Example:
+    /// ```rust,no_run
+    /// # tokio_test::block_on(async {
+    /// # use wit_ai_rs::client::WitClient;
+    /// # let wit_client = WitClient::new(String::new(), String::new());
+    /// for issue in wit_client.validate_utterances().await.unwrap() {
+    ///     println!("\"{}\" references missing {:?}", issue.text, issue.missing);
+    /// }
+    /// # })
+    /// ```
+    pub async fn validate_utterances(&self) -> Result<Vec<ValidationIssue>, Error> {
+        let (intents, entities, traits) =
+            futures::try_join!(self.get_intents(), self.get_entities(), self.get_traits())?;
+
+        let intents: HashSet<String> = intents.into_iter().map(|intent| intent.name).collect();
+        let entities: HashSet<String> = entities.into_iter().map(|entity| entity.name).collect();
+        let traits: HashSet<String> = traits.into_iter().map(|trait_| trait_.name).collect();
+
+        let exists =
+            |names: &HashSet<String>, name: &str| name.starts_with("wit$") || names.contains(name);
+
+        let pages: Vec<UtterancePage> = self.export_utterances_from(0).try_collect().await?;
+
+        let mut issues = Vec::new();
+
+        for utterance in pages.into_iter().flat_map(|page| page.utterances) {
+            let mut missing = Vec::new();
+
+            if let Some(intent) = &utterance.intent {
+                if !exists(&intents, &intent.name) {
+                    missing.push(UtteranceReference::Intent(intent.name.clone()));
+                }
+            }
+
+            let mut pending: Vec<&UtteranceResponseEntity> = utterance.entities.iter().collect();
+
+            while let Some(entity) = pending.pop() {
+                if !exists(&entities, &entity.name) {
+                    missing.push(UtteranceReference::Entity(entity.name.clone()));
+                }
+
+                pending.extend(&entity.entities);
+            }
+
+            for trait_ in &utterance.traits {
+                if !exists(&traits, &trait_.name) {
+                    missing.push(UtteranceReference::Trait(trait_.name.clone()));
+                }
+            }
+
+            issues.extend(missing.into_iter().map(|missing| ValidationIssue {
+                text: utterance.text.clone(),
+                missing,
+            }));
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Buffers new utterances and creates them in batches, flushing automatically once a maximum
+/// batch size is reached or, optionally, once the oldest buffered utterance has been waiting
+/// longer than a maximum delay.
+///
+/// The delay is only checked when an utterance is added, since no background task is spawned.
+/// Async code cannot run on drop, so `close` must be awaited to flush any remaining utterances
+/// before the batcher is dropped--if it is dropped with utterances still buffered, they are lost
+/// and a warning is logged.
+///
+/// Example:
+/// ```rust,no_run
+/// # tokio_test::block_on(async {
+/// # use wit_ai_rs::client::WitClient;
+/// # use wit_ai_rs::utterances::{NewUtterance, UtteranceBatcher};
+/// # use std::time::Duration;
+/// # let wit_client = WitClient::new(String::new(), String::new());
+/// let mut batcher = UtteranceBatcher::new(wit_client, 100)
+///     .unwrap()
+///     .max_delay(Duration::from_secs(30));
+///
+/// for index in 0..250 {
+///     let utterance = NewUtterance::new(format!("utterance {index}"), vec![], vec![], None);
+///
+///     batcher.add(utterance).await.unwrap();
+/// }
+///
+/// // send the last 50 utterances
+/// batcher.close().await.unwrap();
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct UtteranceBatcher {
+    client: WitClient,
+    max_batch_size: usize,
+    max_delay: Option<Duration>,
+    buffer: Vec<NewUtterance>,
+    oldest: Option<Instant>,
+}
+
+impl UtteranceBatcher {
+    /// Create a new `UtteranceBatcher` that creates utterances with the given client once
+    /// `max_batch_size` (at least 1) utterances have been buffered
+    pub fn new(client: WitClient, max_batch_size: usize) -> Result<Self, Error> {
+        if max_batch_size == 0 {
+            return Err(Error::InvalidArgument(String::from(
+                "max batch size for an utterance batcher must be at least 1",
+            )));
+        }
+
+        Ok(Self {
+            client,
+            max_batch_size,
+            max_delay: None,
+            buffer: Vec::new(),
+            oldest: None,
+        })
+    }
+
+    /// Also flush when an utterance is added and the oldest buffered utterance has been
+    /// waiting for at least `max_delay`
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = Some(max_delay);
+        self
+    }
+
+    /// The number of utterances currently buffered
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Whether there are no utterances currently buffered
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Buffer an utterance, flushing if the batch size or delay threshold has been reached.
+    /// Returns the response from wit if a flush happened
+    pub async fn add(
+        &mut self,
+        utterance: NewUtterance,
+    ) -> Result<Option<CreateUtteranceResponse>, Error> {
+        self.buffer.push(utterance);
+
+        let oldest = *self.oldest.get_or_insert_with(Instant::now);
+
+        let delay_reached = self
+            .max_delay
+            .is_some_and(|max_delay| oldest.elapsed() >= max_delay);
+
+        if self.buffer.len() >= self.max_batch_size || delay_reached {
+            return self.flush().await;
+        }
+
+        Ok(None)
+    }
+
+    /// Create all buffered utterances now, returning the response from wit, or None if there were
+    /// no buffered utterances. If creating the utterances fails, they remain buffered
+    pub async fn flush(&mut self) -> Result<Option<CreateUtteranceResponse>, Error> {
+        if self.buffer.is_empty() {
+            return Ok(None);
+        }
+
+        let response = self
+            .client
+            .create_utterances_batched(self.buffer.clone(), self.max_batch_size)
+            .await?;
+
+        self.buffer.clear();
+        self.oldest = None;
+
+        Ok(Some(response))
+    }
+
+    /// Flush any remaining utterances and close the batcher
+    pub async fn close(mut self) -> Result<Option<CreateUtteranceResponse>, Error> {
+        self.flush().await
+    }
+}
+
+impl Drop for UtteranceBatcher {
+    fn drop(&mut self) {
+        if !self.buffer.is_empty() {
+            log::warn!(
+                "UtteranceBatcher dropped with {} buffered utterances that were never created--call `close` before dropping it",
+                self.buffer.len()
+            );
+        }
+    }
 }
@@ -0,0 +1,309 @@
+use futures::TryStreamExt;
+use mockito::Matcher;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use wit_ai_rs::{
+    apps::{AppInfo, TrainingStatus},
+    client::WitClient,
+    errors::Error,
+};
+
+#[tokio::test]
+#[ignore]
+async fn whoami() {
+    let token = std::env::var("WIT_TOKEN").unwrap();
+
+    let client = WitClient::new(token, String::from("20231231"));
+
+    let _response = client.whoami().await.unwrap();
+}
+
+#[tokio::test]
+async fn whoami_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/apps/get_all.json") // copied from docs
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), String::from("1")),
+            Matcher::UrlEncoded(String::from("offset"), String::from("0")),
+        ]))
+        .create();
+
+    let response = client.whoami().await.unwrap();
+
+    let expected_response = AppInfo {
+        id: String::from("2802177596527671"),
+        name: String::from("alarm-clock"),
+        lang: String::from("en"),
+    };
+
+    assert_eq!(response, expected_response);
+
+    mock_apps.assert();
+}
+
+#[tokio::test]
+async fn whoami_invalid_token_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("BAD_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(401)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Bad auth, check token/params", "code": "no-auth"}"#)
+        .match_header("Authorization", "Bearer BAD_TOKEN")
+        .match_query(Matcher::Any)
+        .create();
+
+    let error = client.whoami().await.unwrap_err();
+
+    assert!(matches!(error, Error::AuthError(ref response) if response.code == "no-auth"));
+    assert!(!error.is_retryable());
+
+    mock_apps.assert();
+}
+
+#[tokio::test]
+async fn whoami_no_apps_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body("[]")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let error = client.whoami().await.unwrap_err();
+
+    assert!(matches!(error, Error::NoAppForToken));
+    assert!(!error.is_retryable());
+
+    mock_apps.assert();
+}
+
+#[tokio::test]
+async fn get_app_language_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/apps/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let language = client.get_app_language().await.unwrap();
+
+    assert_eq!(language, "en");
+
+    mock_apps.assert();
+}
+
+#[tokio::test]
+async fn training_status_stream_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    // the app is only looked up once, rather than on every poll
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/apps/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .expect(1)
+        .create();
+
+    let mut mock_statuses = Vec::new();
+
+    for status in ["scheduled", "ongoing", "done"] {
+        let mock = server
+            .mock("GET", "/apps/2802177596527671")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(format!(
+                r#"{{"id": "2802177596527671", "name": "alarm-clock", "lang": "en", "training_status": "{status}"}}"#
+            ))
+            .match_header("Authorization", "Bearer TEST_TOKEN")
+            .match_query(Matcher::UrlEncoded(
+                String::from("v"),
+                client.get_version().to_owned(),
+            ))
+            .expect(1)
+            .create();
+
+        mock_statuses.push(mock);
+    }
+
+    let statuses: Vec<TrainingStatus> = client
+        .training_status_stream(Duration::from_millis(10), CancellationToken::new())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        statuses,
+        vec![
+            TrainingStatus::Scheduled,
+            TrainingStatus::Ongoing,
+            TrainingStatus::Done
+        ]
+    );
+
+    mock_apps.assert();
+
+    for mock in mock_statuses {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn training_status_stream_cancelled_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/apps/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let mock_status = server
+        .mock("GET", "/apps/2802177596527671")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"id": "2802177596527671", "name": "alarm-clock", "lang": "en", "training_status": "ongoing"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .expect(1)
+        .create();
+
+    let cancellation = CancellationToken::new();
+
+    let mut statuses =
+        Box::pin(client.training_status_stream(Duration::from_secs(60), cancellation.clone()));
+
+    assert_eq!(
+        statuses.try_next().await.unwrap(),
+        Some(TrainingStatus::Ongoing)
+    );
+
+    // cancelling stops the wait for the next poll, rather than waiting for the full interval
+    cancellation.cancel();
+
+    let next = tokio::time::timeout(Duration::from_secs(1), statuses.try_next())
+        .await
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(next, None);
+
+    mock_apps.assert();
+    mock_status.assert();
+}
+
+#[tokio::test]
+async fn get_training_status_app_not_found_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_apps = server
+        .mock("GET", "/apps")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/apps/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let mock_app = server
+        .mock("GET", "/apps/2802177596527671")
+        .with_status(404)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Not found", "code": "not-found"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let error = client.get_training_status().await.unwrap_err();
+
+    assert!(matches!(error, Error::AppNotFound(ref source) if source.code == "not-found"));
+
+    mock_apps.assert();
+    mock_app.assert();
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::load_entity;
+use wit_ai_rs::{
+    builtins::{AmountOfMoney, Duration, ResolvedLocation, Temperature},
+    message::Coordinates,
+};
+
+#[test]
+fn amount_of_money() {
+    let entity = load_entity("tests/files/builtins/amount_of_money.json");
+
+    let expected = AmountOfMoney {
+        value: 100.0,
+        unit: String::from("$"),
+    };
+
+    assert_eq!(entity.as_amount_of_money(), Some(expected));
+    assert_eq!(entity.as_duration(), None);
+    assert_eq!(entity.as_temperature(), None);
+}
+
+#[test]
+fn amount_of_money_interval() {
+    let entity = load_entity("tests/files/builtins/amount_of_money_interval.json");
+
+    assert_eq!(entity.as_amount_of_money(), None);
+}
+
+#[test]
+fn duration() {
+    let entity = load_entity("tests/files/builtins/duration.json");
+
+    let expected = Duration {
+        value: 30.0,
+        unit: String::from("minute"),
+    };
+
+    assert_eq!(entity.as_duration(), Some(expected));
+    assert_eq!(entity.as_amount_of_money(), None);
+}
+
+#[test]
+fn duration_as_std_duration() {
+    let entity = load_entity("tests/files/builtins/duration.json");
+
+    assert_eq!(
+        entity.as_std_duration(),
+        Some(std::time::Duration::from_secs(30 * 60))
+    );
+}
+
+#[test]
+fn duration_unnormalized_as_std_duration() {
+    let entity = load_entity("tests/files/builtins/duration_unnormalized.json");
+
+    assert_eq!(entity.normalized, None);
+    assert_eq!(
+        entity.as_std_duration(),
+        Some(std::time::Duration::from_secs(90 * 60))
+    );
+    assert_eq!(
+        load_entity("tests/files/builtins/temperature.json").as_std_duration(),
+        None
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn duration_as_chrono_duration() {
+    let entity = load_entity("tests/files/builtins/duration.json");
+
+    assert_eq!(
+        entity.as_chrono_duration(),
+        Some(chrono::Duration::minutes(30))
+    );
+}
+
+#[test]
+fn location() {
+    let entity = load_entity("tests/files/builtins/location.json");
+
+    let expected = ResolvedLocation {
+        name: String::from("Paris"),
+        coords: Some(Coordinates::new(48.856614, 2.3522219)),
+        grain: Some(String::from("locality")),
+    };
+
+    assert_eq!(entity.as_location(), Some(expected));
+    assert_eq!(entity.as_duration(), None);
+}
+
+#[test]
+fn location_unresolved() {
+    let entity = load_entity("tests/files/builtins/location_unresolved.json");
+
+    let expected = ResolvedLocation {
+        name: String::from("my home town"),
+        coords: None,
+        grain: None,
+    };
+
+    assert_eq!(entity.as_location(), Some(expected));
+    assert_eq!(
+        load_entity("tests/files/builtins/temperature.json").as_location(),
+        None
+    );
+}
+
+#[test]
+fn temperature() {
+    let entity = load_entity("tests/files/builtins/temperature.json");
+
+    let expected = Temperature {
+        value: 72.0,
+        unit: String::from("degree"),
+    };
+
+    assert_eq!(entity.as_temperature(), Some(expected));
+    assert_eq!(entity.as_duration(), None);
+}
@@ -0,0 +1,963 @@
+mod common;
+
+use common::{init_logger, LOGGER};
+use mockito::Matcher;
+use reqwest::Method;
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use wit_ai_rs::{
+    client::{
+        AcceptMode, ResponseMeta, RetryPolicy, WitClient, DEFAULT_USER_AGENT, KNOWN_VERSIONS,
+        LATEST_KNOWN_VERSION,
+    },
+    errors::Error,
+    message::MessageOptions,
+    provision::AppSpec,
+    utterances::NewUtterance,
+};
+
+#[tokio::test]
+async fn accept_version_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_accept_version(String::from("20240215"));
+
+    let mock_intents = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "application/vnd.wit.20240215+json")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    assert_eq!(client.get_version(), "20231231");
+    assert_eq!(client.get_accept_version(), "20240215");
+
+    client.get_intents().await.unwrap();
+
+    mock_intents.assert();
+}
+
+#[tokio::test]
+async fn accept_header_json_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_accept_header(AcceptMode::Json);
+
+    let mock_intents = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "application/json")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    client.get_intents().await.unwrap();
+
+    mock_intents.assert();
+}
+
+#[tokio::test]
+async fn with_token_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let first_client = WitClient::new(String::from("FIRST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let second_client = first_client.with_token(String::from("SECOND_TOKEN"));
+
+    let mock_first = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer FIRST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let mock_second = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer SECOND_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    first_client.get_intents().await.unwrap();
+    second_client.get_intents().await.unwrap();
+
+    mock_first.assert();
+    mock_second.assert();
+}
+
+#[tokio::test]
+async fn request_empty_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_empty = server
+        .mock("DELETE", "/intents/some_intent")
+        .with_status(200)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let mock_no_content = server
+        .mock("PUT", "/intents/other_intent")
+        .with_status(204)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    client
+        .request_empty(
+            Method::DELETE,
+            "/intents/some_intent",
+            vec![],
+            Option::<()>::None,
+        )
+        .await
+        .unwrap();
+
+    client
+        .request_empty(
+            Method::PUT,
+            "/intents/other_intent",
+            vec![],
+            Option::<()>::None,
+        )
+        .await
+        .unwrap();
+
+    mock_empty.assert();
+    mock_no_content.assert();
+}
+
+#[tokio::test]
+async fn malformed_body_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_malformed = server
+        .mock("GET", "/entities")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body("not json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let mock_empty = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let response = client.get_entities().await;
+
+    assert!(matches!(response, Err(Error::ResponseParseError(_))));
+
+    // an empty body is only accepted where the response type can represent nothing
+    let response = client.get_intents().await;
+
+    assert!(matches!(response, Err(Error::ResponseParseError(_))));
+
+    mock_malformed.assert();
+    mock_empty.assert();
+}
+
+#[tokio::test]
+async fn user_agent_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let default_client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let custom_client = default_client
+        .clone()
+        .set_user_agent(String::from("my-app/1.0"));
+
+    let mock_default = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("User-Agent", DEFAULT_USER_AGENT)
+        .match_query(Matcher::Any)
+        .create();
+
+    let mock_custom = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("User-Agent", "my-app/1.0")
+        .match_query(Matcher::Any)
+        .create();
+
+    assert!(DEFAULT_USER_AGENT.starts_with("wit-ai-rs/"));
+
+    default_client.get_intents().await.unwrap();
+    custom_client.get_intents().await.unwrap();
+
+    mock_default.assert();
+    mock_custom.assert();
+}
+
+#[tokio::test]
+async fn slow_request_warning_mock() {
+    init_logger();
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_slow_request_threshold(Duration::from_millis(200));
+
+    let mock_slow = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_chunked_body(|writer| {
+            std::thread::sleep(Duration::from_millis(400));
+            writer.write_all(b"[]")
+        })
+        .match_query(Matcher::Any)
+        .create();
+
+    let mock_fast = server
+        .mock("GET", "/entities")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body("[]")
+        .match_query(Matcher::Any)
+        .create();
+
+    client.get_intents().await.unwrap();
+    client.get_entities().await.unwrap();
+
+    let warnings = LOGGER.warnings.lock().unwrap();
+
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.starts_with("slow request: GET /intents took")));
+    assert!(!warnings.iter().any(|warning| warning.contains("/entities")));
+    assert!(!warnings
+        .iter()
+        .any(|warning| warning.contains("TEST_TOKEN")));
+
+    mock_slow.assert();
+    mock_fast.assert();
+}
+
+#[tokio::test]
+async fn retry_sends_same_body_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(10)));
+
+    let expected_body =
+        r#"[{"text":"make the volume 30","entities":[],"traits":[],"intent":"set_volume"}]"#;
+
+    let mock_unavailable = server
+        .mock("POST", "/utterances")
+        .with_status(503)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Service unavailable", "code": "unavailable"}"#)
+        .match_query(Matcher::Any)
+        .match_header("Content-Type", "application/json")
+        .match_body(expected_body)
+        .expect(1)
+        .create();
+
+    let mock_created = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"sent": true, "n": 1}"#)
+        .match_query(Matcher::Any)
+        .match_header("Content-Type", "application/json")
+        .match_body(expected_body)
+        .expect(1)
+        .create();
+
+    let utterance = NewUtterance::new(
+        String::from("make the volume 30"),
+        vec![],
+        vec![],
+        Some(String::from("set_volume")),
+    );
+
+    let response = client.create_utterances(vec![utterance]).await.unwrap();
+
+    assert_eq!(response.n, 1);
+
+    mock_unavailable.assert();
+    mock_created.assert();
+}
+
+#[tokio::test]
+async fn connection_pool_settings_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_pool_max_idle_per_host(4)
+        .set_pool_idle_timeout(Duration::from_secs(30))
+        .set_user_agent(String::from("my-app/1.0"));
+
+    assert_eq!(client.get_pool_max_idle_per_host(), Some(4));
+    assert_eq!(
+        client.get_pool_idle_timeout(),
+        Some(Duration::from_secs(30))
+    );
+
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("User-Agent", "my-app/1.0")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(2)
+        .create();
+
+    // the rebuilt client is used for requests, and keeps the other settings
+    client.get_intents().await.unwrap();
+    client.get_intents().await.unwrap();
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn update_token_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("OLD_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let clone = client.clone();
+    let other_app = client.with_token(String::from("OTHER_TOKEN"));
+
+    let mock_old = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer OLD_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(1)
+        .create();
+
+    let mock_new = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer NEW_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(2)
+        .create();
+
+    let mock_other = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_header("Authorization", "Bearer OTHER_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(1)
+        .create();
+
+    client.get_intents().await.unwrap();
+
+    client.update_token(String::from("NEW_TOKEN"));
+
+    // clones share the token, but a client for another app does not
+    client.get_intents().await.unwrap();
+    clone.get_intents().await.unwrap();
+    other_app.get_intents().await.unwrap();
+
+    mock_old.assert();
+    mock_new.assert();
+    mock_other.assert();
+}
+
+#[tokio::test]
+async fn max_response_size_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_max_response_size(1000);
+
+    let oversized_body = format!(r#"[{{"id": "1", "name": "{}"}}]"#, "a".repeat(2000));
+
+    let mock_small = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_all.json")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let mock_large = server
+        .mock("GET", "/intents/large")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(&oversized_body)
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    // without a Content-Length header, so the limit is only reached while reading
+    let mock_chunked = server
+        .mock("GET", "/intents/chunked")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_chunked_body(move |writer| writer.write_all(oversized_body.as_bytes()))
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    // an error body under the limit that is not JSON is reported as it was before the limit
+    let mock_malformed_error = server
+        .mock("GET", "/intents/malformed")
+        .with_status(400)
+        .with_body("Bad Request")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    client.get_intents().await.unwrap();
+
+    for name in ["large", "chunked"] {
+        let error = client.get_intent(name).await.unwrap_err();
+
+        assert!(matches!(error, Error::ResponseTooLarge(1000)));
+        assert!(!error.is_retryable());
+    }
+
+    let error = client.get_intent("malformed").await.unwrap_err();
+
+    assert!(matches!(error, Error::ResponseParseError(_)));
+
+    mock_small.assert();
+    mock_large.assert();
+    mock_chunked.assert();
+    mock_malformed_error.assert();
+}
+
+#[tokio::test]
+async fn dropping_request_stops_retries_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_retry_policy(RetryPolicy::new(5).initial_backoff(Duration::from_millis(100)));
+
+    let mock_unavailable = server
+        .mock("GET", "/intents")
+        .with_status(503)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Service unavailable", "code": "unavailable"}"#)
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(1)
+        .create();
+
+    // the request is dropped while waiting to retry after the first attempt
+    let result = tokio::time::timeout(Duration::from_millis(50), client.get_intents()).await;
+
+    assert!(result.is_err());
+
+    // long enough for every retry to have been sent, had the request kept going
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    mock_unavailable.assert();
+}
+
+#[tokio::test]
+async fn max_concurrent_requests_mock() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let max_in_flight = Arc::new(AtomicUsize::new(0));
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_max_concurrent_requests(2)
+        .unwrap();
+
+    let mock = {
+        let in_flight = in_flight.clone();
+        let max_in_flight = max_in_flight.clone();
+
+        server
+            .mock("GET", "/intents")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_chunked_body(move |writer| {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+
+                std::thread::sleep(Duration::from_millis(100));
+
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                writer.write_all(b"[]")
+            })
+            .match_header("Authorization", "Bearer TEST_TOKEN")
+            .match_query(Matcher::UrlEncoded(
+                String::from("v"),
+                String::from("20231231"),
+            ))
+            .expect(6)
+            .create()
+    };
+
+    let responses = futures::future::join_all(
+        (0..6)
+            .map(|_| client.clone())
+            .map(|client| async move { client.get_intents().await }),
+    )
+    .await;
+
+    mock.assert();
+
+    assert!(responses.iter().all(Result::is_ok));
+    assert!(max_in_flight.load(Ordering::SeqCst) <= 2);
+}
+
+#[test]
+fn max_concurrent_requests_zero() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"));
+
+    assert!(matches!(
+        client.set_max_concurrent_requests(0),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn set_api_host_invalid() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"));
+
+    assert!(matches!(
+        client.clone().set_api_host(String::from("not a url")),
+        Err(Error::URLParseError(_))
+    ));
+    assert!(matches!(
+        client.set_api_host(String::from("ftp://api.wit.ai")),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[tokio::test]
+async fn set_api_host_trailing_slash_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = format!("{}/", server.url());
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body("[]")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    client.get_intents().await.unwrap();
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn request_bytes_mock() {
+    // not valid UTF-8, so it would fail to parse as JSON
+    let data: Vec<u8> = vec![0x50, 0x4b, 0x03, 0x04, 0x00, 0xff, 0xfe, 0x80];
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("GET", "/export")
+        .with_status(200)
+        .with_header("Content-Type", "application/zip")
+        .with_body(&data)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "application/zip")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let response = client
+        .request_bytes(
+            Method::GET,
+            "/export",
+            vec![],
+            Option::<()>::None,
+            "application/zip",
+        )
+        .await
+        .unwrap();
+
+    mock.assert();
+
+    assert_eq!(response, data);
+}
+
+#[tokio::test]
+async fn retry_deadline_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_retry_policy(
+            RetryPolicy::new(u32::MAX)
+                .initial_backoff(Duration::from_millis(50))
+                .deadline(Duration::from_millis(300)),
+        );
+
+    // retried after 50ms and then 100ms more, but the next wait of 200ms would pass the deadline
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(503)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Service unavailable", "code": "unavailable"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(3)
+        .create();
+
+    let start = std::time::Instant::now();
+
+    let error = client.get_intents().await.unwrap_err();
+
+    assert!(start.elapsed() < Duration::from_millis(300));
+    assert!(error.is_retryable());
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn retry_deadline_max_backoff_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_retry_policy(
+            RetryPolicy::new(1)
+                .initial_backoff(Duration::MAX)
+                .deadline(Duration::from_secs(1)),
+        );
+
+    // a backoff that would overflow when added to the elapsed time is simply past the deadline
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(503)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Service unavailable", "code": "unavailable"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(1)
+        .create();
+
+    let error = client.get_intents().await.unwrap_err();
+
+    assert!(error.is_retryable());
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn timeout_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_timeout(Duration::from_millis(100));
+
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_chunked_body(|writer| {
+            std::thread::sleep(Duration::from_millis(400));
+            writer.write_all(b"[]")
+        })
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let error = client.get_intents().await.unwrap_err();
+
+    assert!(error.is_timeout());
+    assert!(error.is_retryable());
+
+    mock.assert();
+}
+
+#[test]
+fn latest_known_version() {
+    assert_eq!(KNOWN_VERSIONS.last(), Some(&LATEST_KNOWN_VERSION));
+    assert!(KNOWN_VERSIONS.windows(2).all(|pair| pair[0] < pair[1]));
+
+    let client = WitClient::new_with_latest_version("TEST_TOKEN");
+
+    assert_eq!(client.get_version(), LATEST_KNOWN_VERSION);
+}
+
+#[tokio::test]
+async fn deprecation_notice_mock() {
+    init_logger();
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_deprecated = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_header("Deprecation", "@1735689599")
+        .with_header("Sunset", "Wed, 31 Dec 2025 23:59:59 GMT")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("q"),
+            String::from("hello"),
+        ))
+        .create();
+
+    // a `Warning` header is not necessarily about deprecation, so it is not reported
+    let mock_current = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_header("Warning", r#"110 - "Response is Stale""#)
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("q"),
+            String::from("goodbye"),
+        ))
+        .create();
+
+    let (_, meta) = client
+        .message_with_meta(String::from("hello"), MessageOptions::default())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        meta.deprecation_notices(),
+        vec![
+            String::from("deprecation: @1735689599"),
+            String::from("sunset: Wed, 31 Dec 2025 23:59:59 GMT"),
+        ]
+    );
+
+    // without a `Deprecation` or `Sunset` header, there is nothing to report
+    let (_, meta) = client
+        .message_with_meta(String::from("goodbye"), MessageOptions::default())
+        .await
+        .unwrap();
+
+    assert!(meta.deprecation_notices().is_empty());
+
+    mock_deprecated.assert();
+    mock_current.assert();
+
+    let warnings = LOGGER.warnings.lock().unwrap();
+
+    assert!(warnings.iter().any(|warning| warning
+        == "deprecation notice from wit for /message: sunset: Wed, 31 Dec 2025 23:59:59 GMT"));
+    assert!(!warnings.iter().any(|warning| warning.contains("Stale")));
+}
+
+#[tokio::test]
+async fn capture_meta_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("GET", "/entities")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_header("X-Test-Header", "value")
+        .with_body_from_file("tests/files/entities/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let (entities, meta) = ResponseMeta::capture(client.get_entities()).await.unwrap();
+
+    let meta = meta.unwrap();
+
+    assert!(!entities.is_empty());
+    assert!(meta.elapsed > Duration::ZERO);
+    assert_eq!(meta.retries, 0);
+    assert_eq!(meta.status, 200);
+    assert_eq!(meta.headers["X-Test-Header"], "value");
+
+    // a call that sends no request has no metadata
+    let ((), meta) = ResponseMeta::capture(client.provision(AppSpec::default()))
+        .await
+        .unwrap();
+
+    assert!(meta.is_none());
+
+    mock.assert();
+}
@@ -0,0 +1,65 @@
+//! Fixtures shared between the integration tests
+
+// each test binary only uses some of these
+#![allow(dead_code)]
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use mockito::{Matcher, Mock, ServerGuard};
+use std::sync::Mutex;
+use wit_ai_rs::message::MessageEntity;
+
+/// Records all warnings logged during the test
+pub struct WarningLogger {
+    pub warnings: Mutex<Vec<String>>,
+}
+
+impl Log for WarningLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Warn
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            self.warnings
+                .lock()
+                .unwrap()
+                .push(record.args().to_string());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+pub static LOGGER: WarningLogger = WarningLogger {
+    warnings: Mutex::new(Vec::new()),
+};
+
+/// Install `LOGGER`, which can only be done once per test binary
+pub fn init_logger() {
+    // fails if another test has already installed it, which is fine
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Warn);
+}
+
+/// Parse the message entity in the JSON file at `path`
+pub fn load_entity(path: &str) -> MessageEntity {
+    let contents = std::fs::read_to_string(path).unwrap();
+
+    serde_json::from_str(&contents).unwrap()
+}
+
+/// Mock a successful `method` request to `endpoint`, made with the test token and version,
+/// that responds with the JSON in `file`
+pub fn mock_endpoint(server: &mut ServerGuard, method: &str, endpoint: &str, file: &str) -> Mock {
+    server
+        .mock(method, endpoint)
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file(file)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create()
+}
@@ -0,0 +1,82 @@
+use mockito::Matcher;
+use wit_ai_rs::{
+    client::{WitClient, LATEST_KNOWN_VERSION},
+    config::{RetryConfig, WitConfig},
+    errors::Error,
+};
+
+#[test]
+fn deserialize_config() {
+    let config: WitConfig = serde_json::from_str(include_str!("files/config/config.json")).unwrap();
+
+    assert_eq!(
+        config,
+        WitConfig {
+            token: None,
+            version: String::from("20231231"),
+            api_host: Some(String::from("https://wit.example.com/")),
+            timeout_ms: Some(5000),
+            user_agent: Some(String::from("my-app/1.0")),
+            retry: Some(RetryConfig {
+                max_retries: 3,
+                initial_backoff_ms: Some(250),
+                deadline_ms: Some(2000),
+            }),
+        }
+    );
+}
+
+#[test]
+fn from_config_without_token() {
+    let config: WitConfig = serde_json::from_str(r#"{"version": "20231231"}"#).unwrap();
+
+    let result = WitClient::from_config(config);
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn from_config_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let config = WitConfig {
+        token: Some(String::from("TEST_TOKEN")),
+        api_host: Some(url),
+        retry: Some(RetryConfig {
+            max_retries: 1,
+            initial_backoff_ms: Some(10),
+            deadline_ms: None,
+        }),
+        ..serde_json::from_str(include_str!("files/config/config.json")).unwrap()
+    };
+
+    // the first attempt fails and is retried once, as configured
+    let mock = server
+        .mock("GET", "/intents")
+        .with_status(503)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Service unavailable", "code": "unavailable"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("User-Agent", "my-app/1.0")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .expect(2)
+        .create();
+
+    let wit_client = WitClient::from_config(config).unwrap();
+
+    wit_client.get_intents().await.unwrap_err();
+
+    mock.assert();
+}
+
+#[test]
+fn config_default_version() {
+    let config: WitConfig = serde_json::from_str(r#"{"token": "TEST_TOKEN"}"#).unwrap();
+
+    assert_eq!(config.version, LATEST_KNOWN_VERSION);
+}
@@ -0,0 +1,305 @@
+mod common;
+
+use common::{init_logger, LOGGER};
+use futures::StreamExt;
+use mockito::Matcher;
+use wit_ai_rs::{
+    client::WitClient,
+    common_types::AudioType,
+    dictation::{Token, TokenAlternative},
+    errors::Error,
+};
+
+#[tokio::test]
+async fn dictation_wav_warning_mock() {
+    init_logger();
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_dictation = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/wav")
+        .match_query(Matcher::Any)
+        .expect(2)
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    for _ in 0..2 {
+        let responses: Vec<_> = client
+            .dictation(vec![0u8; 16], AudioType::WAV)
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(responses.is_empty());
+    }
+
+    let warnings = LOGGER.warnings.lock().unwrap();
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].contains("WAV"));
+    assert!(warnings[0].contains("use MP3 or raw PCM"));
+
+    mock_dictation.assert();
+}
+
+#[tokio::test]
+async fn dictation_from_reader_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let audio: Vec<u8> = (0..10_000).map(|index| (index % 256) as u8).collect();
+
+    let mock_dictation = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body_from_file("tests/files/dictation/response.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/mpeg")
+        .match_query(Matcher::Any)
+        .match_body(audio.clone())
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let reader = std::io::Cursor::new(audio);
+
+    let responses: Vec<_> = client
+        .dictation_from_reader(reader, AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(responses.len(), 2);
+
+    let last = responses.last().unwrap().as_ref().unwrap();
+
+    assert_eq!(last.text, "hello world");
+    assert_eq!(last.is_final, Some(true));
+
+    mock_dictation.assert();
+}
+
+#[tokio::test]
+async fn dictation_max_response_size_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_dictation = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body_from_file("tests/files/dictation/response.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::Any)
+        .create();
+
+    // the first result fits within the limit, but the second does not
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_max_response_size(250);
+
+    let responses: Vec<_> = client
+        .dictation(vec![0u8; 16], AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0].as_ref().unwrap().text, "hello");
+    assert!(matches!(responses[1], Err(Error::ResponseTooLarge(250))));
+
+    mock_dictation.assert();
+}
+
+#[test]
+fn token_alternatives() {
+    let contents =
+        std::fs::read_to_string("tests/files/dictation/token_alternatives.json").unwrap();
+
+    let token: Token = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(token.token, "write");
+    assert_eq!(
+        token.alternatives,
+        vec![
+            TokenAlternative {
+                token: String::from("right"),
+                confidence: 0.31,
+            },
+            TokenAlternative {
+                token: String::from("rite"),
+                confidence: 0.07,
+            },
+        ]
+    );
+
+    let without_alternatives: Token =
+        serde_json::from_str(r#"{"confidence": 0.9, "start": 0, "end": 480, "token": "hello"}"#)
+            .unwrap();
+
+    assert!(without_alternatives.alternatives.is_empty());
+}
+
+#[tokio::test]
+async fn dictation_transfer_encoding_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let audio: Vec<u8> = (0..1_000).map(|index| (index % 256) as u8).collect();
+
+    // a body of known length is sent with only a `Content-Length`
+    let mock_sized = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body("")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Length", "1000")
+        .match_header("Transfer-Encoding", Matcher::Missing)
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(audio.clone())
+        .create();
+
+    let responses: Vec<_> = client
+        .dictation(audio.clone(), AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(responses.is_empty());
+    mock_sized.assert();
+
+    // a streamed body is chunked exactly once
+    let mock_streamed = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body("")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Length", Matcher::Missing)
+        .match_header("Transfer-Encoding", "chunked")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(audio.clone())
+        .create();
+
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        audio.chunks(300).map(|chunk| Ok(chunk.to_vec())).collect();
+
+    let responses: Vec<_> = client
+        .dictation(
+            reqwest::Body::wrap_stream(futures::stream::iter(chunks)),
+            AudioType::MP3,
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(responses.is_empty());
+    mock_streamed.assert();
+}
+
+#[tokio::test]
+async fn dictation_streaming_version_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap()
+        .set_streaming_version(String::from("20230215"));
+
+    let mock = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body("")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20230215"),
+        ))
+        .create();
+
+    assert_eq!(client.get_version(), "20231231");
+    assert_eq!(client.get_streaming_version(), "20230215");
+
+    client
+        .dictation(vec![0u8; 16], AudioType::MP3)
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn dictation_with_length_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let audio: Vec<u8> = (0..1_000).map(|index| (index % 256) as u8).collect();
+
+    let mock = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body("")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Length", "1000")
+        .match_header("Transfer-Encoding", Matcher::Missing)
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(audio.clone())
+        .create();
+
+    // a streamed body, which would otherwise be chunked
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        audio.chunks(300).map(|chunk| Ok(chunk.to_vec())).collect();
+
+    let responses: Vec<_> = client
+        .dictation_with_length(
+            reqwest::Body::wrap_stream(futures::stream::iter(chunks)),
+            AudioType::MP3,
+            audio.len() as u64,
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(responses.is_empty());
+    mock.assert();
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::mock_endpoint;
+use wit_ai_rs::{
+    client::WitClient,
+    diff::{diff_apps, AppDiff, ResourceDiff},
+};
+
+#[tokio::test]
+async fn diff_apps_mock() {
+    let mut server_a = mockito::Server::new_async().await;
+    let mut server_b = mockito::Server::new_async().await;
+
+    let client_a = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(server_a.url())
+        .unwrap();
+    let client_b = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(server_b.url())
+        .unwrap();
+
+    let mocks = vec![
+        mock_endpoint(
+            &mut server_a,
+            "GET",
+            "/intents",
+            "tests/files/intents/get_all.json",
+        ),
+        mock_endpoint(
+            &mut server_a,
+            "GET",
+            "/entities",
+            "tests/files/entities/get_all.json",
+        ),
+        mock_endpoint(
+            &mut server_a,
+            "GET",
+            "/traits",
+            "tests/files/traits/get_all.json",
+        ),
+        mock_endpoint(
+            &mut server_b,
+            "GET",
+            "/intents",
+            "tests/files/diff/intents.json",
+        ),
+        mock_endpoint(
+            &mut server_b,
+            "GET",
+            "/entities",
+            "tests/files/diff/entities.json",
+        ),
+        mock_endpoint(
+            &mut server_b,
+            "GET",
+            "/traits",
+            "tests/files/diff/traits.json",
+        ),
+    ];
+
+    let expected_diff = AppDiff {
+        intents: ResourceDiff {
+            added: vec![String::from("sell_car")],
+            removed: vec![
+                String::from("make_call"),
+                String::from("wit$get_weather"),
+                String::from("wit$play_music"),
+            ],
+            common: vec![String::from("buy_car")],
+        },
+        entities: ResourceDiff {
+            added: vec![],
+            removed: vec![
+                String::from("color"),
+                String::from("wit$amount_of_money"),
+                String::from("wit$reminder"),
+            ],
+            common: vec![String::from("car"), String::from("wit$datetime")],
+        },
+        traits: ResourceDiff {
+            added: vec![],
+            removed: vec![String::from("faq"), String::from("politeness")],
+            common: vec![String::from("wit$sentiment")],
+        },
+    };
+
+    let diff = diff_apps(&client_a, &client_b).await.unwrap();
+
+    assert_eq!(diff, expected_diff);
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
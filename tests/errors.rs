@@ -0,0 +1,102 @@
+use reqwest::StatusCode;
+use wit_ai_rs::{
+    client::WitClient,
+    errors::{Error, ErrorResponse},
+    provision::ProvisionStep,
+};
+
+fn wit_error(status: Option<StatusCode>) -> Error {
+    Error::WitError(ErrorResponse {
+        error: String::from("an error"),
+        code: String::from("an-error"),
+        status,
+    })
+}
+
+#[test]
+fn wit_error_is_retryable() {
+    assert!(wit_error(Some(StatusCode::TOO_MANY_REQUESTS)).is_retryable());
+    assert!(wit_error(Some(StatusCode::INTERNAL_SERVER_ERROR)).is_retryable());
+    assert!(wit_error(Some(StatusCode::SERVICE_UNAVAILABLE)).is_retryable());
+
+    assert!(!wit_error(Some(StatusCode::BAD_REQUEST)).is_retryable());
+    assert!(!wit_error(Some(StatusCode::NOT_FOUND)).is_retryable());
+    assert!(!wit_error(None).is_retryable());
+}
+
+#[test]
+fn other_errors_are_not_retryable() {
+    assert!(!Error::InvalidArgument(String::from("bad limit")).is_retryable());
+    assert!(!Error::JSONParseError(String::from("bad json")).is_retryable());
+    assert!(!Error::Conflict(String::from("changed")).is_retryable());
+}
+
+#[test]
+fn provision_error_is_retryable() {
+    let retryable = Error::ProvisionError(
+        ProvisionStep::Utterances,
+        Box::new(wit_error(Some(StatusCode::BAD_GATEWAY))),
+    );
+
+    let not_retryable = Error::ProvisionError(
+        ProvisionStep::Utterances,
+        Box::new(wit_error(Some(StatusCode::BAD_REQUEST))),
+    );
+
+    assert!(retryable.is_retryable());
+    assert!(!not_retryable.is_retryable());
+}
+
+#[tokio::test]
+async fn connection_error_is_retryable() {
+    // nothing should be listening on this port
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(String::from("http://127.0.0.1:1"))
+        .unwrap();
+
+    let err = client.get_intents().await.unwrap_err();
+
+    assert!(matches!(err, Error::RequestError(_)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn connection_error_classification() {
+    // nothing should be listening on this port
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(String::from("http://127.0.0.1:1"))
+        .unwrap();
+
+    let err = client.get_intents().await.unwrap_err();
+
+    assert!(err.is_connect());
+    assert!(!err.is_dns());
+    assert!(!err.is_timeout());
+
+    let provision_err = Error::ProvisionError(ProvisionStep::Utterances, Box::new(err));
+
+    assert!(provision_err.is_connect());
+}
+
+#[tokio::test]
+async fn dns_error_classification() {
+    // the .invalid top-level domain is reserved, so it never resolves
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(String::from("http://wit-ai-rs.invalid"))
+        .unwrap();
+
+    let err = client.get_intents().await.unwrap_err();
+
+    assert!(err.is_dns());
+    assert!(err.is_connect());
+    assert!(!err.is_timeout());
+}
+
+#[test]
+fn non_request_errors_are_unclassified() {
+    let err = wit_error(Some(StatusCode::GATEWAY_TIMEOUT));
+
+    assert!(!err.is_connect());
+    assert!(!err.is_timeout());
+    assert!(!err.is_dns());
+}
@@ -0,0 +1,129 @@
+use mockito::Matcher;
+use wit_ai_rs::{
+    client::WitClient,
+    errors::Error,
+    evaluation::{CaseResult, ConfusionPair},
+    message::MessageOptions,
+    Confidence,
+};
+
+fn message_body(text: &str, intent: Option<(&str, f64)>) -> String {
+    let intents = match intent {
+        Some((name, confidence)) => {
+            format!(r#"[{{"id": "1", "name": "{name}", "confidence": {confidence}}}]"#)
+        }
+        None => String::from("[]"),
+    };
+
+    format!(r#"{{"text": "{text}", "intents": {intents}, "entities": {{}}, "traits": {{}}}}"#)
+}
+
+#[tokio::test]
+async fn evaluate_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let cases = [
+        ("book a flight", Some(("book_flight", 0.97))),
+        ("what's the weather", Some(("book_flight", 0.55))),
+        ("is it raining", None),
+    ];
+
+    let mut mocks = Vec::new();
+
+    for (query, intent) in cases {
+        let mock = server
+            .mock("GET", "/message")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(message_body(query, intent))
+            .match_header("Authorization", "Bearer TEST_TOKEN")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+                Matcher::UrlEncoded(String::from("q"), String::from(query)),
+            ]))
+            .create();
+
+        mocks.push(mock);
+    }
+
+    let report = client
+        .evaluate(
+            vec![
+                (String::from("book a flight"), String::from("book_flight")),
+                (
+                    String::from("what's the weather"),
+                    String::from("get_weather"),
+                ),
+                (String::from("is it raining"), String::from("get_weather")),
+            ],
+            MessageOptions::default(),
+            2,
+        )
+        .await
+        .unwrap();
+
+    for mock in mocks {
+        mock.assert();
+    }
+
+    assert_eq!(report.results.len(), 3);
+    assert!(report.results[0].is_correct());
+    assert!((report.accuracy() - 1.0 / 3.0).abs() < f64::EPSILON);
+
+    assert_eq!(
+        report.confusion_pairs(),
+        vec![
+            ConfusionPair {
+                expected: String::from("get_weather"),
+                predicted: None,
+                count: 1,
+            },
+            ConfusionPair {
+                expected: String::from("get_weather"),
+                predicted: Some(String::from("book_flight")),
+                count: 1,
+            },
+        ]
+    );
+
+    let low_confidence: Vec<&CaseResult> = report.low_confidence(0.7).collect();
+
+    assert_eq!(
+        low_confidence,
+        vec![
+            &CaseResult {
+                query: String::from("what's the weather"),
+                expected_intent: String::from("get_weather"),
+                predicted_intent: Some(String::from("book_flight")),
+                confidence: Some(Confidence::new(0.55).unwrap()),
+            },
+            &CaseResult {
+                query: String::from("is it raining"),
+                expected_intent: String::from("get_weather"),
+                predicted_intent: None,
+                confidence: None,
+            },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn evaluate_zero_concurrency() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"));
+
+    let result = client
+        .evaluate(
+            vec![(String::from("book a flight"), String::from("book_flight"))],
+            MessageOptions::default(),
+            0,
+        )
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+}
@@ -1,6 +1,11 @@
+// the live tests pass the token as an owned `String`, to show that callers written before
+// `WitClient::new` accepted `impl Into<String>` still compile
+#![allow(clippy::useless_conversion)]
+
 use mockito::Matcher;
 use wit_ai_rs::{
-    client::WitClient, intents::IntentResponse, DeleteResponse, EntityBasic, IntentBasic,
+    client::WitClient, errors::Error, intents::IntentResponse, DeleteResponse, EntityBasic,
+    IntentBasic,
 };
 
 #[tokio::test]
@@ -54,8 +59,9 @@ async fn get_all_intents_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("GET", "/intents")
@@ -100,8 +106,9 @@ async fn create_intent_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("POST", "/intents")
@@ -134,8 +141,9 @@ async fn get_intent_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("GET", "/intents/buy_flowers")
@@ -178,8 +186,9 @@ async fn delete_intent_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("DELETE", "/intents/buy_flowers")
@@ -205,3 +214,168 @@ async fn delete_intent_mock() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_intent_not_found_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("GET", "/intents/missing")
+        .with_status(404)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Intent not found", "code": "not-found"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let error = client.get_intent("missing").await.unwrap_err();
+
+    assert!(matches!(error, Error::IntentNotFound(name, _) if name == "missing"));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn get_intents_detailed_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let list_mock = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"[{"id": "1", "name": "buy_flowers"}, {"id": "2", "name": "make_call"}]"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let buy_flowers_mock = server
+        .mock("GET", "/intents/buy_flowers")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_one.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let make_call_mock = server
+        .mock("GET", "/intents/make_call")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"id": "2", "name": "make_call", "entities": []}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let response = client.get_intents_detailed(2).await.unwrap();
+
+    list_mock.assert();
+    buy_flowers_mock.assert();
+    make_call_mock.assert();
+
+    let names: Vec<&str> = response.iter().map(|intent| intent.name.as_str()).collect();
+    assert_eq!(names, vec!["buy_flowers", "make_call"]);
+
+    assert_eq!(response[0].entities.len(), 2);
+    assert_eq!(
+        response[1],
+        IntentResponse {
+            id: String::from("2"),
+            name: String::from("make_call"),
+            entities: vec![],
+        }
+    );
+}
+
+#[tokio::test]
+async fn get_intents_detailed_failure_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let list_mock = server
+        .mock("GET", "/intents")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"[{"id": "1", "name": "buy_flowers"}, {"id": "2", "name": "make_call"}]"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    // may be cancelled before it is sent once make_call fails, so it is not asserted
+    let _buy_flowers_mock = server
+        .mock("GET", "/intents/buy_flowers")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/intents/get_one.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let make_call_mock = server
+        .mock("GET", "/intents/make_call")
+        .with_status(500)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Internal error", "code": "internal"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let error = client.get_intents_detailed(2).await.unwrap_err();
+
+    match error {
+        Error::IntentError(name, source) => {
+            assert_eq!(name, "make_call");
+            assert!(matches!(*source, Error::WitError(_)));
+        }
+        other => panic!("expected an IntentError, got {other:?}"),
+    }
+
+    list_mock.assert();
+    make_call_mock.assert();
+}
+
+#[tokio::test]
+async fn get_intents_detailed_zero_concurrency() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"));
+
+    let result = client.get_intents_detailed(0).await;
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+}
@@ -1,6 +1,11 @@
+// the live tests pass the token as an owned `String`, to show that callers written before
+// `WitClient::new` accepted `impl Into<String>` still compile
+#![allow(clippy::useless_conversion)]
+
 use mockito::Matcher;
 use wit_ai_rs::{
     client::WitClient,
+    errors::Error,
     language::{LanguageResponse, Locale},
 };
 
@@ -49,8 +54,9 @@ async fn language_mock() {
         ],
     };
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let query = "bonjour les amis";
 
@@ -61,4 +67,67 @@ async fn language_mock() {
     mock_language.assert();
 }
 
+#[tokio::test]
+async fn detect_languages_batch_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let queries = ["bonjour", "hello", "hola", "ciao", "hallo", "ola"];
+
+    // the most delayed response is for the first query, so the order of the results does not
+    // depend on the order the responses arrive in
+    let mocks: Vec<_> = queries
+        .iter()
+        .enumerate()
+        .map(|(index, query)| {
+            let body =
+                format!(r#"{{"detected_locales": [{{"locale": "{query}", "confidence": 0.9}}]}}"#);
+            let delay = std::time::Duration::from_millis(50 * (queries.len() - index) as u64);
+
+            server
+                .mock("GET", "/language")
+                .with_status(200)
+                .with_header("Content-Type", "application/json")
+                .with_chunked_body(move |writer| {
+                    std::thread::sleep(delay);
+                    writer.write_all(body.as_bytes())
+                })
+                .match_header("Authorization", "Bearer TEST_TOKEN")
+                .match_query(Matcher::AllOf(vec![
+                    Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+                    Matcher::UrlEncoded(String::from("q"), String::from(*query)),
+                    Matcher::UrlEncoded(String::from("n"), String::from("1")),
+                ]))
+                .create()
+        })
+        .collect();
+
+    let responses = client
+        .detect_languages_batch(queries.iter().map(|query| query.to_string()).collect(), 1)
+        .await
+        .unwrap();
+
+    let locales: Vec<&str> = responses
+        .iter()
+        .map(|response| response.detected_locales[0].locale.as_str())
+        .collect();
+
+    assert_eq!(locales, queries);
+
+    for mock in mocks {
+        mock.assert();
+    }
+
+    let invalid = client
+        .detect_languages_batch(vec![String::from("hello")], 9)
+        .await;
+
+    assert!(matches!(invalid, Err(Error::InvalidArgument(_))));
+}
+
 // TODO: test language url params
@@ -1,12 +1,22 @@
+// the live tests pass the token as an owned `String`, to show that callers written before
+// `WitClient::new` accepted `impl Into<String>` still compile
+#![allow(clippy::useless_conversion)]
+
+mod common;
+
+use common::load_entity;
 use mockito::Matcher;
 use serde_json::Value;
 use std::collections::HashMap;
 use wit_ai_rs::{
     client::WitClient,
+    errors::Error,
     message::{
-        ContextBuilder, Coordinates, IntervalEndpoint, MessageEntity, MessageIntent,
-        MessageOptions, MessageOptionsBuilder, MessageResponse,
+        ConfidenceChange, ContextBuilder, Coordinates, Grain, IntentChange, Interval,
+        IntervalEndpoint, MessageEntity, MessageIntent, MessageOptions, MessageOptionsBuilder,
+        MessageResponse,
     },
+    Confidence, DynamicEntities, DynamicEntity, EntityKeyword, MAX_DYNAMIC_ENTITIES_LENGTH,
 };
 
 #[tokio::test]
@@ -55,8 +65,9 @@ async fn message_mock() {
         ))
         .create();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let query = "how many people between Tuesday and Friday";
 
@@ -77,7 +88,12 @@ async fn message_mock() {
             end: 15,
             body: String::from("people"),
             value: Some(serde_json::Value::String(String::from("metric_visitor"))),
-            confidence: 0.9231,
+            unit: None,
+            grain: None,
+            normalized: None,
+            values: None,
+            resolved: None,
+            confidence: Confidence::new(0.9231).unwrap(),
             entities: HashMap::new(),
             from: None,
             to: None,
@@ -94,16 +110,38 @@ async fn message_mock() {
             end: 42,
             body: String::from("between Tuesday and Friday"),
             value: None,
-            confidence: 0.9541,
+            unit: None,
+            grain: None,
+            normalized: None,
+            values: Some(
+                [("05", "09"), ("12", "16"), ("19", "23")]
+                    .iter()
+                    .map(|(from, to)| {
+                        serde_json::json!({
+                            "type": "interval",
+                            "from": {
+                                "grain": "day",
+                                "value": format!("2020-05-{from}T00:00:00.000-07:00")
+                            },
+                            "to": {
+                                "grain": "day",
+                                "value": format!("2020-05-{to}T00:00:00.000-07:00")
+                            }
+                        })
+                    })
+                    .collect(),
+            ),
+            resolved: None,
+            confidence: Confidence::new(0.9541).unwrap(),
             entities: HashMap::new(),
             from: Some(IntervalEndpoint {
                 unit: None,
-                grain: Some(String::from("day")),
+                grain: Some(Grain::Day),
                 value: Value::String(String::from("2020-05-05T00:00:00.000-07:00")),
             }),
             to: Some(IntervalEndpoint {
                 unit: None,
-                grain: Some(String::from("day")),
+                grain: Some(Grain::Day),
                 value: Value::String(String::from("2020-05-09T00:00:00.000-07:00")),
             }),
         }],
@@ -118,10 +156,11 @@ async fn message_mock() {
         intents: vec![MessageIntent {
             id: String::from("1701608719981716"),
             name: String::from("inquiry"),
-            confidence: 0.8849,
+            confidence: Confidence::new(0.8849).unwrap(),
         }],
         entities,
         traits,
+        debug: None,
     };
 
     assert_eq!(response, expected_response);
@@ -129,4 +168,941 @@ async fn message_mock() {
     mock_message.assert();
 }
 
+#[tokio::test]
+async fn message_dynamic_entities_from_iter_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let entities: DynamicEntities = ["Paris", "Seoul", "Paris"]
+        .into_iter()
+        .enumerate()
+        .map(|(index, city)| {
+            DynamicEntity::new(
+                String::from("city"),
+                vec![EntityKeyword::new(
+                    String::from(city),
+                    vec![format!("{city}_{index}")],
+                )],
+            )
+        })
+        .collect();
+
+    // both "Paris" entities should be merged into one keyword, rather than overwriting each other
+    let expected_entities = r#"{"entities":{"city":[{"keyword":"Paris","synonyms":["Paris_0","Paris_2"]},{"keyword":"Seoul","synonyms":["Seoul_1"]}]}}"#;
+
+    let mock_message = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("entities"),
+            String::from(expected_entities),
+        ))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let options = MessageOptionsBuilder::new()
+        .dynamic_entities(entities)
+        .build();
+
+    client
+        .message(String::from("fly to Paris"), options)
+        .await
+        .unwrap();
+
+    mock_message.assert();
+}
+
+#[tokio::test]
+async fn message_extra_param_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_message = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+            Matcher::UrlEncoded(String::from("q"), String::from("fly to Paris")),
+            Matcher::UrlEncoded(String::from("beta_flag"), String::from("true")),
+        ]))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let options = MessageOptionsBuilder::new()
+        .extra_param(String::from("beta_flag"), String::from("true"))
+        .unwrap()
+        .build();
+
+    client
+        .message(String::from("fly to Paris"), options)
+        .await
+        .unwrap();
+
+    mock_message.assert();
+
+    for reserved in ["v", "q", "tag"] {
+        let result =
+            MessageOptionsBuilder::new().extra_param(String::from(reserved), String::from("x"));
+
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+    }
+}
+
+#[test]
+fn dynamic_entities_from_map() {
+    let keywords = |city: &str| {
+        vec![
+            EntityKeyword::new(String::from(city), vec![String::from(city)]),
+            EntityKeyword::new(format!("{city} Airport"), vec![]),
+        ]
+    };
+
+    let from_vec = DynamicEntities::new(vec![
+        DynamicEntity::new(String::from("departure"), keywords("Paris")),
+        DynamicEntity::new(String::from("destination"), keywords("Seoul")),
+        DynamicEntity::new(String::from("layover"), keywords("Dubai")),
+    ]);
+
+    let map = || {
+        HashMap::from([
+            (String::from("layover"), keywords("Dubai")),
+            (String::from("departure"), keywords("Paris")),
+            (String::from("destination"), keywords("Seoul")),
+        ])
+    };
+
+    let from_map = DynamicEntities::from_map(map());
+    let converted: DynamicEntities = map().into();
+
+    let expected = serde_json::to_string(&from_vec).unwrap();
+
+    assert_eq!(serde_json::to_string(&from_map).unwrap(), expected);
+    assert_eq!(serde_json::to_string(&converted).unwrap(), expected);
+}
+
+#[test]
+fn dynamic_entities_from_simple() {
+    let explicit = DynamicEntities::new(vec![
+        DynamicEntity::new(
+            "contact",
+            vec![
+                EntityKeyword::new("Alice", vec![String::from("Alice")]),
+                EntityKeyword::new("Bob", vec![String::from("Bob")]),
+            ],
+        ),
+        DynamicEntity::new(
+            "city",
+            vec![EntityKeyword::new("Paris", vec![String::from("Paris")])],
+        ),
+    ]);
+
+    let simple =
+        DynamicEntities::from_simple(&[("contact", &["Alice", "Bob"]), ("city", &["Paris"])]);
+
+    assert_eq!(
+        serde_json::to_string(&simple).unwrap(),
+        serde_json::to_string(&explicit).unwrap()
+    );
+}
+
+#[tokio::test]
+async fn message_dynamic_entities_too_large() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(String::from("http://localhost"))
+        .unwrap();
+
+    let keywords = (0..MAX_DYNAMIC_ENTITIES_LENGTH / 10)
+        .map(|index| EntityKeyword::new(format!("keyword_{index}"), vec![]))
+        .collect();
+
+    let entities = DynamicEntities::new(vec![DynamicEntity::new(String::from("city"), keywords)]);
+
+    let options = MessageOptionsBuilder::new()
+        .dynamic_entities(entities)
+        .build();
+
+    let response = client.message(String::from("fly to Paris"), options).await;
+
+    assert!(matches!(response, Err(Error::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn message_url_too_long_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    // the request is rejected before anything is sent
+    let mock_message = server
+        .mock("GET", "/message")
+        .match_query(Matcher::Any)
+        .expect(0)
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    // well within `MAX_DYNAMIC_ENTITIES_LENGTH`, but too long for the URL
+    let keywords = (0..1000)
+        .map(|index| EntityKeyword::new(format!("keyword_{index}"), vec![]))
+        .collect();
+
+    let entities = DynamicEntities::new(vec![DynamicEntity::new(String::from("city"), keywords)]);
+
+    let options = MessageOptionsBuilder::new()
+        .dynamic_entities(entities)
+        .build();
+
+    let response = client
+        .message(
+            String::from("how many people between Tuesday and Friday"),
+            options,
+        )
+        .await;
+
+    assert!(matches!(response, Err(Error::InvalidArgument(_))));
+
+    mock_message.assert();
+}
+
+#[test]
+fn grain_from_string() {
+    let grains = [
+        ("second", Grain::Second),
+        ("minute", Grain::Minute),
+        ("hour", Grain::Hour),
+        ("day", Grain::Day),
+        ("week", Grain::Week),
+        ("month", Grain::Month),
+        ("quarter", Grain::Quarter),
+        ("year", Grain::Year),
+        ("decade", Grain::Other(String::from("decade"))),
+    ];
+
+    for (grain_str, expected_grain) in grains {
+        let grain: Grain = serde_json::from_value(Value::String(String::from(grain_str))).unwrap();
+
+        assert_eq!(grain, expected_grain);
+    }
+}
+
+#[tokio::test]
+async fn message_with_meta_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_message = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_header("X-Test-Header", "value")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("q"),
+            String::from("how many people between Tuesday and Friday"),
+        ))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let start = std::time::Instant::now();
+
+    let (response, meta) = client
+        .message_with_meta(
+            String::from("how many people between Tuesday and Friday"),
+            MessageOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.text, "how many people between Tuesday and Friday");
+    assert!(meta.elapsed > std::time::Duration::ZERO);
+    assert!(meta.elapsed <= start.elapsed());
+    assert_eq!(meta.retries, 0);
+    assert_eq!(meta.status, 200);
+    assert_eq!(meta.headers["X-Test-Header"], "value");
+
+    mock_message.assert();
+}
+
+#[tokio::test]
+async fn message_verbose_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_message = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(
+            r#"{"text": "hello", "intents": [], "entities": {}, "traits": {}, "debug": {"resolved": false}}"#,
+        )
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("q"), String::from("hello")),
+            Matcher::UrlEncoded(String::from("verbose"), String::from("true")),
+        ]))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let options = MessageOptionsBuilder::new().verbose(true).build();
+
+    let response = client
+        .message(String::from("hello"), options)
+        .await
+        .unwrap();
+
+    assert_eq!(response.debug, Some(serde_json::json!({"resolved": false})));
+
+    mock_message.assert();
+}
+
+#[test]
+fn message_top_intent() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(
+        response.top_intent().map(|intent| intent.name.as_str()),
+        Some("inquiry")
+    );
+    assert_eq!(
+        response
+            .intent_above(0.8)
+            .map(|intent| intent.name.as_str()),
+        Some("inquiry")
+    );
+    assert_eq!(response.intent_above(0.9), None);
+
+    let no_intents = MessageResponse {
+        intents: vec![],
+        ..response
+    };
+
+    assert_eq!(no_intents.top_intent(), None);
+}
+
+#[test]
+fn message_nbest_intents() {
+    // deliberately out of order, so that the sorting is checked
+    let response: MessageResponse = serde_json::from_str(
+        r#"{
+            "text": "play some jazz",
+            "intents": [
+                {"id": "1", "name": "play_album", "confidence": 0.21},
+                {"id": "2", "name": "play_genre", "confidence": 0.93},
+                {"id": "3", "name": "play_radio", "confidence": 0.47}
+            ],
+            "entities": {},
+            "traits": {}
+        }"#,
+    )
+    .unwrap();
+
+    let ranked: Vec<(usize, &str)> = response
+        .nbest_intents()
+        .map(|(rank, intent)| (rank, intent.name.as_str()))
+        .collect();
+
+    assert_eq!(
+        ranked,
+        vec![(1, "play_genre"), (2, "play_radio"), (3, "play_album")]
+    );
+
+    let top: Vec<(usize, &str)> = response
+        .top_k(2)
+        .map(|(rank, intent)| (rank, intent.name.as_str()))
+        .collect();
+
+    assert_eq!(top, vec![(1, "play_genre"), (2, "play_radio")]);
+    assert_eq!(response.top_k(10).count(), 3);
+}
+
+#[tokio::test]
+async fn message_tags_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let query = "how many people between Tuesday and Friday";
+
+    let mock_stable = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+            Matcher::UrlEncoded(String::from("q"), String::from(query)),
+            Matcher::UrlEncoded(String::from("tag"), String::from("stable")),
+        ]))
+        .create();
+
+    let mock_candidate = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message/tagged.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+            Matcher::UrlEncoded(String::from("q"), String::from(query)),
+            Matcher::UrlEncoded(String::from("tag"), String::from("candidate")),
+        ]))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let stable = client
+        .message(
+            query.to_string(),
+            MessageOptionsBuilder::new()
+                .tag(String::from("stable"))
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    let candidate = client
+        .message(
+            query.to_string(),
+            MessageOptionsBuilder::new()
+                .tag(String::from("candidate"))
+                .build(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stable.top_intent().map(|intent| intent.name.as_str()),
+        Some("inquiry")
+    );
+    assert_eq!(
+        candidate.top_intent().map(|intent| intent.name.as_str()),
+        Some("report")
+    );
+
+    mock_stable.assert();
+    mock_candidate.assert();
+}
+
+#[test]
+fn message_trait_as() {
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum Sentiment {
+        Positive,
+        Neutral,
+        Negative,
+    }
+
+    let contents = std::fs::read_to_string("tests/files/message/traits.json").unwrap();
+
+    let response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(
+        response
+            .trait_as::<Sentiment>("wit$sentiment")
+            .unwrap()
+            .unwrap(),
+        Sentiment::Negative
+    );
+    assert!(matches!(
+        response.trait_as::<Sentiment>("priority"),
+        Some(Err(Error::JSONParseError(_)))
+    ));
+    assert_eq!(response.trait_as::<u8>("priority").unwrap().unwrap(), 3);
+    assert!(response.trait_as::<Sentiment>("missing").is_none());
+}
+
+#[test]
+fn message_approx_eq() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let expected: MessageResponse = serde_json::from_str(&contents).unwrap();
+    let mut actual: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    actual.intents[0].confidence = Confidence::new(0.8851).unwrap();
+    actual.entities.get_mut("metric:metric").unwrap()[0].confidence =
+        Confidence::new(0.9229).unwrap();
+
+    assert!(actual.approx_eq(&expected, 0.001));
+    assert!(!actual.approx_eq(&expected, 0.0001));
+    assert_eq!(actual.approx_diff(&expected, 0.0001).len(), 2);
+    assert!(actual.approx_diff(&expected, 0.0001)[0].starts_with("intents[0].confidence: "));
+
+    actual.entities.get_mut("metric:metric").unwrap()[0].body = String::from("visitors");
+
+    assert_eq!(
+        actual.approx_diff(&expected, 0.001),
+        vec![String::from(
+            r#"entities["metric:metric"][0].body: "visitors" != "people""#
+        )]
+    );
+}
+
+#[test]
+fn confidence_out_of_range() {
+    let too_high: MessageIntent =
+        serde_json::from_str(r#"{"id": "1", "name": "inquiry", "confidence": 1.2}"#).unwrap();
+    let too_low: MessageIntent =
+        serde_json::from_str(r#"{"id": "1", "name": "inquiry", "confidence": -0.1}"#).unwrap();
+
+    assert_eq!(too_high.confidence.into_inner(), 1.0);
+    assert_eq!(too_low.confidence.into_inner(), 0.0);
+
+    assert!(matches!(
+        Confidence::new(1.2),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        Confidence::new(f64::NAN),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert_eq!(Confidence::new(0.5).unwrap().into_inner(), 0.5);
+}
+
+#[test]
+fn numeric_precision() {
+    let response: MessageResponse = serde_json::from_str(
+        r#"{
+            "text": "hello",
+            "intents": [{"id": 1234567890123456789, "name": "greeting", "confidence": 0.30000000000000004}],
+            "entities": {},
+            "traits": {
+                "wit$greetings": [{"id": 9223372036854775807, "value": "true", "confidence": 0.9999999999999999}]
+            }
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(response.intents[0].id, "1234567890123456789");
+    assert_eq!(
+        response.traits["wit$greetings"][0].id,
+        "9223372036854775807"
+    );
+
+    // parsed exactly as the standard library does, rather than to the nearest few bits
+    assert_eq!(
+        response.intents[0].confidence.into_inner(),
+        "0.30000000000000004".parse::<f64>().unwrap()
+    );
+    assert_eq!(
+        response.traits["wit$greetings"][0].confidence.into_inner(),
+        "0.9999999999999999".parse::<f64>().unwrap()
+    );
+}
+
+fn day_endpoint(value: &str) -> IntervalEndpoint {
+    IntervalEndpoint {
+        unit: None,
+        grain: Some(Grain::Day),
+        value: Value::String(String::from(value)),
+    }
+}
+
+#[test]
+fn interval_lower_bound_only() {
+    let entity = load_entity("tests/files/message/interval_lower.json");
+
+    let interval = entity.interval().unwrap();
+
+    assert_eq!(
+        interval,
+        Interval {
+            from: Some(day_endpoint("2020-05-05T00:00:00.000-07:00")),
+            to: None,
+        }
+    );
+    assert!(interval.is_open_ended());
+}
+
+#[test]
+fn interval_upper_bound_only() {
+    let entity = load_entity("tests/files/message/interval_upper.json");
+
+    let interval = entity.interval().unwrap();
+
+    assert_eq!(
+        interval,
+        Interval {
+            from: None,
+            to: Some(day_endpoint("2020-05-05T00:00:00.000-07:00")),
+        }
+    );
+    assert!(interval.is_open_ended());
+}
+
+#[test]
+fn composite_sub_entities() {
+    let flight = load_entity("tests/files/message/composite.json");
+
+    fn body(entity: Option<&MessageEntity>) -> Option<&str> {
+        entity.map(|entity| entity.body.as_str())
+    }
+
+    assert_eq!(
+        body(flight.sub_entity("wit$location", Some("origin"))),
+        Some("Paris")
+    );
+    assert_eq!(
+        body(flight.sub_entity("wit$location", Some("destination"))),
+        Some("Seoul")
+    );
+    assert_eq!(
+        body(flight.sub_entity("wit$datetime", None)),
+        Some("tomorrow morning")
+    );
+    assert!(flight
+        .sub_entity("wit$datetime", None)
+        .unwrap()
+        .interval()
+        .is_some());
+
+    // without a role, the default role does not exist, so the first role by name is used
+    assert_eq!(body(flight.sub_entity("wit$location", None)), Some("Seoul"));
+
+    assert_eq!(flight.sub_entity("wit$location", Some("layover")), None);
+    assert_eq!(flight.sub_entity("wit$number", None), None);
+
+    let mut bodies: Vec<&str> = flight
+        .sub_entities()
+        .map(|entity| entity.body.as_str())
+        .collect();
+    bodies.sort();
+
+    assert_eq!(bodies, vec!["Paris", "Seoul", "tomorrow morning"]);
+}
+
+#[test]
+fn interval_both_bounds() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    let datetime = &response.entities["wit$datetime:datetime"][0];
+    let metric = &response.entities["metric:metric"][0];
+
+    let interval = datetime.interval().unwrap();
+
+    assert_eq!(
+        interval,
+        Interval {
+            from: Some(day_endpoint("2020-05-05T00:00:00.000-07:00")),
+            to: Some(day_endpoint("2020-05-09T00:00:00.000-07:00")),
+        }
+    );
+    assert!(!interval.is_open_ended());
+    assert_eq!(metric.interval(), None);
+}
+
 // TODO: test message url params
+
+#[test]
+fn message_to_simple() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    let simple = response.to_simple();
+
+    assert_eq!(simple.intent.as_deref(), Some("inquiry"));
+    assert_eq!(
+        simple.slots,
+        HashMap::from([
+            (String::from("metric"), String::from("metric_visitor")),
+            // an interval has no single value, so the text is used instead
+            (
+                String::from("datetime"),
+                String::from("between Tuesday and Friday")
+            ),
+        ])
+    );
+}
+
+#[test]
+fn message_to_simple_shared_role() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let mut response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    let metric_entity = |name: &str, value: &str, confidence: f64| -> MessageEntity {
+        serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "name": name,
+            "role": "metric",
+            "start": 9,
+            "end": 15,
+            "body": "people",
+            "value": value,
+            "confidence": confidence,
+            "entities": {}
+        }))
+        .unwrap()
+    };
+
+    // the entity wit is more confident in fills the shared slot
+    response.entities.insert(
+        String::from("audience:metric"),
+        vec![metric_entity("audience", "metric_audience", 0.99)],
+    );
+
+    assert_eq!(response.to_simple().slots["metric"], "metric_audience");
+
+    // and of equally confident entities, the one whose key sorts first does
+    response.entities.insert(
+        String::from("attendance:metric"),
+        vec![metric_entity("attendance", "metric_attendance", 0.99)],
+    );
+
+    assert_eq!(response.to_simple().slots["metric"], "metric_attendance");
+}
+
+#[test]
+fn resolved_value_from_values() {
+    let entity = load_entity("tests/files/message/values_only.json");
+
+    assert_eq!(entity.value, None);
+    assert_eq!(entity.values.as_ref().map(Vec::len), Some(2));
+    assert_eq!(
+        entity.resolved_value(),
+        Some(&Value::String(String::from(
+            "2020-05-05T05:00:00.000-07:00"
+        )))
+    );
+
+    // `value` is preferred when it is present
+    let entity = load_entity("tests/files/builtins/duration.json");
+
+    assert_eq!(entity.resolved_value(), Some(&Value::from(30)));
+}
+
+#[tokio::test]
+async fn message_normalize_query_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let query = "  how many   people\tbetween Tuesday and Friday \n";
+
+    let mock_exact = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(String::from("q"), String::from(query)))
+        .create();
+
+    let mock_normalized = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("q"),
+            String::from("how many people between Tuesday and Friday"),
+        ))
+        .create();
+
+    client
+        .message(query, MessageOptions::default())
+        .await
+        .unwrap();
+
+    client
+        .message(
+            query,
+            MessageOptionsBuilder::new().normalize_query(true).build(),
+        )
+        .await
+        .unwrap();
+
+    mock_exact.assert();
+    mock_normalized.assert();
+}
+
+#[tokio::test]
+async fn message_validate_dynamic_entities_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let entity_mock = server
+        .mock("GET", "/entities/first_name")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/entities/get_one.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let message_mock = server
+        .mock("GET", "/message")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/message.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("q"),
+            String::from("call Jane"),
+        ))
+        .create();
+
+    let options = MessageOptionsBuilder::new()
+        .dynamic_entities(DynamicEntities::from_simple(&[("first_name", &["Jane"])]))
+        .validate_dynamic_entities(true)
+        .build();
+
+    client
+        .message(String::from("call Jane"), options)
+        .await
+        .unwrap();
+
+    entity_mock.assert();
+    message_mock.assert();
+}
+
+#[tokio::test]
+async fn message_validate_dynamic_entities_not_keyword_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let entity_mock = server
+        .mock("GET", "/entities/notes")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"id": "1", "name": "notes", "lookups": ["free-text"], "roles": [], "keywords": []}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    // the message is never sent when validation fails
+    let message_mock = server
+        .mock("GET", "/message")
+        .match_query(Matcher::Any)
+        .expect(0)
+        .create();
+
+    let options = MessageOptionsBuilder::new()
+        .dynamic_entities(DynamicEntities::from_simple(&[("notes", &["groceries"])]))
+        .validate_dynamic_entities(true)
+        .build();
+
+    let result = client
+        .message(String::from("remember groceries"), options)
+        .await;
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+
+    entity_mock.assert();
+    message_mock.assert();
+}
+
+#[test]
+fn message_diff() {
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let baseline: MessageResponse = serde_json::from_str(&contents).unwrap();
+    let mut response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    assert!(response.diff(&baseline, 0.05).is_empty());
+    assert_eq!(response.diff(&baseline, 0.05).to_string(), "no changes");
+
+    // a small shift within the tolerance is not reported
+    response.intents[0].confidence = Confidence::new(0.87).unwrap();
+    response.entities.get_mut("metric:metric").unwrap()[0].confidence =
+        Confidence::new(0.5).unwrap();
+
+    let removed = response.entities.remove("wit$datetime:datetime").unwrap();
+    response
+        .entities
+        .insert(String::from("wit$datetime:date"), removed);
+
+    let diff = response.diff(&baseline, 0.05);
+
+    assert_eq!(diff.intent_change, None);
+    assert!(diff.intent_confidence_changes.is_empty());
+    assert_eq!(diff.added_entities, vec![String::from("wit$datetime:date")]);
+    assert_eq!(
+        diff.removed_entities,
+        vec![String::from("wit$datetime:datetime")]
+    );
+    assert_eq!(
+        diff.entity_confidence_changes,
+        vec![ConfidenceChange {
+            name: String::from("metric:metric"),
+            baseline: Confidence::new(0.9231).unwrap(),
+            current: Confidence::new(0.5).unwrap(),
+        }]
+    );
+
+    response.intents[0].name = String::from("greeting");
+
+    let diff = response.diff(&baseline, 0.05);
+
+    assert_eq!(
+        diff.intent_change,
+        Some(IntentChange {
+            baseline: Some(String::from("inquiry")),
+            current: Some(String::from("greeting")),
+        })
+    );
+    assert_eq!(
+        diff.to_string(),
+        [
+            "intent: inquiry -> greeting",
+            "added entity: wit$datetime:date",
+            "removed entity: wit$datetime:datetime",
+            "entity metric:metric confidence: 0.9231 -> 0.5",
+        ]
+        .join("\n")
+    );
+}
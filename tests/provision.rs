@@ -0,0 +1,158 @@
+mod common;
+
+use common::mock_endpoint;
+use mockito::Matcher;
+use wit_ai_rs::{
+    client::WitClient,
+    entities::NewEntityBuilder,
+    errors::Error,
+    provision::{AppSpec, ProvisionStep},
+    traits::NewTrait,
+    utterances::NewUtterance,
+};
+
+#[tokio::test]
+async fn provision_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(server.url())
+        .unwrap();
+
+    let mocks = vec![
+        // entities: "car" already exists, "favorite_city" is created, and the list is fetched
+        // only once for both
+        mock_endpoint(
+            &mut server,
+            "GET",
+            "/entities",
+            "tests/files/entities/get_all.json",
+        )
+        .expect(1),
+        mock_endpoint(
+            &mut server,
+            "GET",
+            "/entities/car",
+            "tests/files/entities/get_one.json",
+        )
+        .expect(0),
+        mock_endpoint(
+            &mut server,
+            "POST",
+            "/entities",
+            "tests/files/entities/create.json",
+        ),
+        // traits: "politeness" already exists
+        mock_endpoint(
+            &mut server,
+            "GET",
+            "/traits",
+            "tests/files/traits/get_all.json",
+        ),
+        mock_endpoint(
+            &mut server,
+            "GET",
+            "/traits/politeness",
+            "tests/files/traits/get_one.json",
+        )
+        .expect(0),
+        // intents: "buy_flowers" is created
+        mock_endpoint(
+            &mut server,
+            "GET",
+            "/intents",
+            "tests/files/intents/get_all.json",
+        ),
+        mock_endpoint(
+            &mut server,
+            "POST",
+            "/intents",
+            "tests/files/intents/create.json",
+        ),
+        mock_endpoint(
+            &mut server,
+            "POST",
+            "/utterances",
+            "tests/files/utterances/create.json",
+        ),
+    ];
+
+    let spec = AppSpec {
+        entities: vec![
+            NewEntityBuilder::new(String::from("car")).build(),
+            NewEntityBuilder::new(String::from("favorite_city")).build(),
+        ],
+        traits: vec![NewTrait::new(
+            String::from("politeness"),
+            vec![String::from("polite"), String::from("rude")],
+        )],
+        intents: vec![String::from("buy_flowers")],
+        utterances: vec![NewUtterance::new(
+            String::from("buy some flowers"),
+            vec![],
+            vec![],
+            Some(String::from("buy_flowers")),
+        )],
+    };
+
+    client.provision(spec).await.unwrap();
+
+    for mock in mocks {
+        mock.assert();
+    }
+}
+
+#[tokio::test]
+async fn provision_failed_step_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(server.url())
+        .unwrap();
+
+    let mock_get_intents = mock_endpoint(
+        &mut server,
+        "GET",
+        "/intents",
+        "tests/files/intents/get_all.json",
+    );
+
+    let mock_create_intent = server
+        .mock("POST", "/intents")
+        .with_status(400)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Invalid intent name", "code": "bad-request"}"#)
+        .match_query(Matcher::Any)
+        .create();
+
+    let mock_utterances = server
+        .mock("POST", "/utterances")
+        .match_query(Matcher::Any)
+        .expect(0)
+        .create();
+
+    let spec = AppSpec {
+        intents: vec![String::from("invalid intent")],
+        utterances: vec![NewUtterance::new(
+            String::from("an utterance"),
+            vec![],
+            vec![],
+            None,
+        )],
+        ..Default::default()
+    };
+
+    let response = client.provision(spec).await;
+
+    match response {
+        Err(Error::ProvisionError(step, source)) => {
+            assert_eq!(step, ProvisionStep::Intent(String::from("invalid intent")));
+            assert!(matches!(*source, Error::WitError(_)));
+        }
+        other => panic!("expected a provision error, got {other:?}"),
+    }
+
+    mock_get_intents.assert();
+    mock_create_intent.assert();
+    mock_utterances.assert();
+}
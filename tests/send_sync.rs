@@ -0,0 +1,72 @@
+//! Compile-time checks that the client and the futures and streams it returns can be used
+//! from multi-threaded runtimes and web frameworks, which require them to be `Send`
+
+use futures::StreamExt;
+use wit_ai_rs::{
+    client::WitClient,
+    common_types::AudioType,
+    message::MessageOptions,
+    utterances::{GetUtterancesRequestBuilder, UtteranceBatcher},
+};
+
+fn assert_send<T: Send>(_: &T) {}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn client_is_send_and_sync() {
+    assert_send_sync::<WitClient>();
+    assert_send_sync::<UtteranceBatcher>();
+}
+
+#[test]
+fn futures_are_send() {
+    let client = WitClient::new("TOKEN", "20240215");
+
+    assert_send(&client.message("some query", MessageOptions::default()));
+    assert_send(&client.message_with_meta("some query", MessageOptions::default()));
+    assert_send(&client.get_entities());
+    assert_send(&client.get_intent("intent"));
+    assert_send(&client.get_utterances(GetUtterancesRequestBuilder::default().build()));
+    assert_send(&client.language("some query", 1));
+}
+
+#[test]
+fn streams_are_send() {
+    let client = WitClient::new("TOKEN", "20240215");
+
+    // each future holds its stream across an await point, so it is only `Send` if the stream is
+    assert_send(&async {
+        let mut speech = Box::pin(client.speech(vec![0u8; 16], AudioType::MP3).await.unwrap());
+        speech.next().await;
+    });
+
+    assert_send(&async {
+        let mut dictation = Box::pin(
+            client
+                .dictation(vec![0u8; 16], AudioType::MP3)
+                .await
+                .unwrap(),
+        );
+        dictation.next().await;
+    });
+
+    assert_send(&async {
+        let mut utterances =
+            Box::pin(client.get_utterances_stream(GetUtterancesRequestBuilder::max().build()));
+        utterances.next().await;
+    });
+
+    assert_send(&async {
+        let mut pages = Box::pin(client.export_utterances_from(0));
+        pages.next().await;
+    });
+
+    assert_send(&async {
+        let mut statuses = Box::pin(client.training_status_stream(
+            std::time::Duration::from_secs(1),
+            tokio_util::sync::CancellationToken::new(),
+        ));
+        statuses.next().await;
+    });
+}
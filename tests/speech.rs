@@ -0,0 +1,632 @@
+use futures::StreamExt;
+use mockito::Matcher;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use wit_ai_rs::{
+    client::WitClient,
+    common_types::{
+        AudioType, Endianness, ProgressBody, RawAudioConfig, RawAudioConfigBuilder, RawEncoding,
+    },
+    errors::Error,
+    message::{ContextBuilder, MessageOptions, MessageOptionsBuilder},
+    speech::{
+        SpeechResponse, SpeechSession, SpeechSink, SpeechStream, TranscriptionResponse,
+        UnderstandingResponse,
+    },
+    Confidence,
+};
+
+/// Records the order in which sink methods are called
+#[derive(Default)]
+struct RecordingSink {
+    events: Vec<String>,
+}
+
+impl SpeechSink for RecordingSink {
+    fn on_transcription(&mut self, transcription: TranscriptionResponse) {
+        self.events
+            .push(format!("transcription: {}", transcription.text));
+    }
+
+    fn on_understanding(&mut self, understanding: UnderstandingResponse) {
+        self.events
+            .push(format!("understanding: {}", understanding.intents[0].name));
+    }
+
+    fn on_error(&mut self, _error: Error) {
+        self.events.push(String::from("error"));
+    }
+}
+
+#[tokio::test]
+async fn speech_progress_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let audio: Vec<u8> = (0..10_000).map(|index| (index % 256) as u8).collect();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/transcription.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/mpeg")
+        .match_query(Matcher::Any)
+        .match_body(audio.clone())
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        audio.chunks(3000).map(|chunk| Ok(chunk.to_vec())).collect();
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let callback_sent = sent.clone();
+
+    let body = ProgressBody::new(futures::stream::iter(chunks), move |bytes_sent| {
+        callback_sent.store(bytes_sent, Ordering::SeqCst);
+    });
+
+    let responses: Vec<_> = client
+        .speech(body, AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(sent.load(Ordering::SeqCst), audio.len() as u64);
+
+    assert_eq!(responses.len(), 2);
+
+    match responses.last() {
+        Some(Ok(SpeechResponse::Transcription(transcription))) => {
+            assert_eq!(
+                transcription.text,
+                "how many people between Tuesday and Friday"
+            )
+        }
+        other => panic!("expected a transcription, got {other:?}"),
+    }
+
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_malformed_understanding_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/malformed_understanding.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::Any)
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let responses: Vec<_> = client
+        .speech(vec![0u8; 16], AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(responses.len(), 2);
+
+    // the understanding response has an entity with an invalid `start`,
+    // but its text should still be returned
+    match responses.last() {
+        Some(Ok(SpeechResponse::Transcription(transcription))) => {
+            assert_eq!(transcription.text, "how many people")
+        }
+        other => panic!("expected a transcription, got {other:?}"),
+    }
+
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_to_sink_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/stream.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::Any)
+        .expect(2)
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mut sink = RecordingSink::default();
+
+    client
+        .speech_to_sink(vec![0u8; 16], AudioType::MP3, &mut sink)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        sink.events,
+        vec![
+            String::from("transcription: how many"),
+            String::from("understanding: inquiry"),
+            String::from("error"),
+            String::from("transcription: how many people between Tuesday and Friday"),
+        ]
+    );
+
+    // closures can also be used as sinks
+    let mut results = Vec::new();
+
+    client
+        .speech_to_sink(vec![0u8; 16], AudioType::MP3, &mut |result| {
+            results.push(result)
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 4);
+    assert!(matches!(results[1], Ok(SpeechResponse::Understanding(_))));
+
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_boxed_mock() {
+    struct Session {
+        results: SpeechStream,
+    }
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/transcription.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::Any)
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let session = Session {
+        results: client
+            .speech_boxed(vec![0u8; 16], AudioType::MP3)
+            .await
+            .unwrap(),
+    };
+
+    let responses: Vec<_> = session.results.collect().await;
+
+    assert_eq!(responses.len(), 2);
+
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_with_options_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/transcription.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), String::from("20231231")),
+            Matcher::UrlEncoded(String::from("tag"), String::from("prod")),
+            Matcher::UrlEncoded(
+                String::from("context"),
+                String::from(
+                    r#"{"reference_time":null,"timezone":"America/Los_Angeles","locale":null,"coords":null}"#,
+                ),
+            ),
+        ]))
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let context = ContextBuilder::new()
+        .timezone(String::from("America/Los_Angeles"))
+        .build();
+
+    let options = MessageOptionsBuilder::new()
+        .tag(String::from("prod"))
+        .context(context)
+        .build();
+
+    let responses: Vec<_> = client
+        .speech_with_options(vec![0u8; 16], AudioType::MP3, options)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert_eq!(responses.len(), 2);
+
+    mock_speech.assert();
+}
+
+#[test]
+fn understanding_top_intent() {
+    let contents = std::fs::read_to_string("tests/files/speech/understanding.json").unwrap();
+
+    let understanding: UnderstandingResponse = serde_json::from_str(&contents).unwrap();
+
+    // the intents in the sample are deliberately not sorted by confidence
+    let top_intent = understanding.top_intent().unwrap();
+
+    assert_eq!(top_intent.name, "set_alarm");
+
+    assert_eq!(
+        understanding
+            .intent_above(0.9)
+            .map(|intent| intent.name.as_str()),
+        Some("set_alarm")
+    );
+    assert_eq!(understanding.intent_above(0.95), None);
+}
+
+#[test]
+fn understanding_top_trait() {
+    let contents = std::fs::read_to_string("tests/files/speech/understanding_traits.json").unwrap();
+
+    let understanding: UnderstandingResponse = serde_json::from_str(&contents).unwrap();
+
+    // every candidate is kept, in the order wit returned them
+    let values: Vec<&str> = understanding.traits["wit$sentiment"]
+        .iter()
+        .map(|candidate| candidate.value.as_str().unwrap())
+        .collect();
+
+    assert_eq!(values, vec!["neutral", "positive", "negative"]);
+
+    let top_trait = understanding.top_trait("wit$sentiment").unwrap();
+
+    assert_eq!(top_trait.value, "positive");
+    assert_eq!(top_trait.confidence.into_inner(), 0.5026);
+
+    assert!(understanding.top_trait("politeness").is_none());
+}
+
+#[test]
+fn speech_session_partials_then_understanding() {
+    let transcription = |text: &str| {
+        SpeechResponse::Transcription(TranscriptionResponse {
+            text: String::from(text),
+        })
+    };
+
+    let contents = std::fs::read_to_string("tests/files/speech/understanding.json").unwrap();
+
+    let understanding: UnderstandingResponse = serde_json::from_str(&contents).unwrap();
+    let final_text = understanding.text.clone();
+
+    let mut session = SpeechSession::new();
+
+    assert_eq!(session.snapshot().current_text, "");
+    assert!(session.snapshot().final_understanding.is_none());
+
+    session.apply(transcription("set an"));
+    assert_eq!(session.snapshot().current_text, "set an");
+
+    session.apply(transcription("set an alarm"));
+    assert_eq!(session.snapshot().current_text, "set an alarm");
+    assert!(session.snapshot().final_understanding.is_none());
+
+    session.apply(SpeechResponse::Understanding(understanding));
+
+    let snapshot = session.snapshot();
+
+    assert_eq!(snapshot.current_text, final_text);
+    assert_eq!(
+        snapshot
+            .final_understanding
+            .and_then(|understanding| understanding.top_intent())
+            .map(|intent| intent.name.as_str()),
+        Some("set_alarm")
+    );
+
+    // the next utterance starts over
+    session.apply(transcription("and"));
+    assert_eq!(session.snapshot().current_text, "and");
+    assert!(session.snapshot().final_understanding.is_none());
+}
+
+#[test]
+fn understanding_approx_eq() {
+    let contents = std::fs::read_to_string("tests/files/speech/understanding.json").unwrap();
+
+    let expected: UnderstandingResponse = serde_json::from_str(&contents).unwrap();
+    let mut actual: UnderstandingResponse = serde_json::from_str(&contents).unwrap();
+
+    let confidence = actual.intents[1].confidence.into_inner();
+    actual.intents[1].confidence = Confidence::new(confidence - 0.002).unwrap();
+
+    assert!(actual.approx_eq(&expected, 0.005));
+    assert!(!actual.approx_eq(&expected, 0.001));
+    assert!(actual.approx_diff(&expected, 0.001)[0].starts_with("intents[1].confidence: "));
+
+    actual.intents.pop();
+
+    assert_eq!(
+        actual.approx_diff(&expected, 0.005),
+        vec![String::from("intents: length 2 != 3")]
+    );
+}
+
+#[test]
+fn audio_type_from_str() {
+    assert_eq!("mp3".parse::<AudioType>().unwrap(), AudioType::MP3);
+    assert_eq!("MP3".parse::<AudioType>().unwrap(), AudioType::MP3);
+    assert_eq!("audio/mpeg".parse::<AudioType>().unwrap(), AudioType::MP3);
+    assert_eq!("wav".parse::<AudioType>().unwrap(), AudioType::WAV);
+    assert_eq!("Audio/WAV".parse::<AudioType>().unwrap(), AudioType::WAV);
+
+    for audio_type in [AudioType::MP3, AudioType::WAV] {
+        assert_eq!(
+            audio_type.to_string().parse::<AudioType>().unwrap(),
+            audio_type
+        );
+    }
+
+    assert!(matches!(
+        "flac".parse::<AudioType>(),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        "".parse::<AudioType>(),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[test]
+fn raw_audio_config_accepted() {
+    let default = RawAudioConfigBuilder::new().build().unwrap();
+
+    assert_eq!(default, RawAudioConfig::default());
+    assert_eq!(
+        AudioType::Raw(default).to_string(),
+        "audio/raw;encoding=signed-integer;bits=16;rate=16000;endian=little"
+    );
+
+    let telephony = RawAudioConfigBuilder::new()
+        .encoding(RawEncoding::MuLaw)
+        .bits(8)
+        .sample_rate(8_000)
+        .endianness(Endianness::Big)
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        AudioType::Raw(telephony).to_string(),
+        "audio/raw;encoding=mu-law;bits=8;rate=8000;endian=big"
+    );
+
+    let float = RawAudioConfigBuilder::new()
+        .encoding(RawEncoding::FloatingPoint)
+        .bits(32)
+        .build()
+        .unwrap();
+
+    // raw audio types parse back from their content type
+    for config in [default, telephony, float] {
+        let audio_type = AudioType::Raw(config);
+
+        assert_eq!(
+            audio_type.to_string().parse::<AudioType>().unwrap(),
+            audio_type
+        );
+    }
+
+    assert_eq!(
+        "raw".parse::<AudioType>().unwrap(),
+        AudioType::Raw(RawAudioConfig::default())
+    );
+}
+
+#[test]
+fn raw_audio_config_rejected() {
+    let rejected = [
+        RawAudioConfigBuilder::new().channels(2).build(),
+        RawAudioConfigBuilder::new().sample_rate(44_100).build(),
+        RawAudioConfigBuilder::new().bits(24).build(),
+        RawAudioConfigBuilder::new()
+            .encoding(RawEncoding::FloatingPoint)
+            .build(),
+        RawAudioConfigBuilder::new()
+            .encoding(RawEncoding::ALaw)
+            .bits(16)
+            .build(),
+    ];
+
+    for result in rejected {
+        assert!(matches!(result, Err(Error::InvalidArgument(_))));
+    }
+
+    // the error lists what wit accepts
+    let Err(Error::InvalidArgument(message)) =
+        RawAudioConfigBuilder::new().sample_rate(44_100).build()
+    else {
+        panic!("expected an invalid argument error");
+    };
+
+    assert!(message.contains("8000 or 16000 Hz"));
+
+    assert!(matches!(
+        "audio/raw;encoding=signed-integer;bits=16;rate=48000;endian=little".parse::<AudioType>(),
+        Err(Error::InvalidArgument(_))
+    ));
+    assert!(matches!(
+        "audio/raw;encoding=opus".parse::<AudioType>(),
+        Err(Error::InvalidArgument(_))
+    ));
+}
+
+#[tokio::test]
+async fn transcribe_and_understand_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let audio: Vec<u8> = (0..1000).map(|index| (index % 256) as u8).collect();
+
+    let mock_dictation = server
+        .mock("POST", "/dictation")
+        .with_status(200)
+        .with_body_from_file("tests/files/dictation/response.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/mpeg")
+        .match_query(Matcher::Any)
+        .match_body(audio.clone())
+        .create();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/understanding.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/mpeg")
+        .match_query(Matcher::Any)
+        .match_body(audio.clone())
+        .create();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let (dictation, speech) = client
+        .transcribe_and_understand(audio, AudioType::MP3)
+        .await
+        .unwrap();
+
+    assert_eq!(dictation.len(), 2);
+    assert_eq!(dictation[1].text, "hello world");
+
+    match speech.as_slice() {
+        [SpeechResponse::Understanding(understanding)] => {
+            assert_eq!(understanding.text, "set an alarm for tomorrow")
+        }
+        other => panic!("expected a single understanding, got {other:?}"),
+    }
+
+    mock_dictation.assert();
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_stream_identity_transform_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let audio: Vec<u8> = (0..10_000).map(|index| (index % 256) as u8).collect();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/transcription.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Type", "audio/mpeg")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(audio.clone())
+        .create();
+
+    let chunks: Vec<bytes::Bytes> = audio
+        .chunks(3000)
+        .map(bytes::Bytes::copy_from_slice)
+        .collect();
+
+    // an identity transform stands in for a user's preprocessing
+    let transformed = futures::stream::iter(chunks).map(|chunk| chunk);
+
+    let responses: Vec<_> = client
+        .speech_stream(transformed, AudioType::MP3)
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(responses.iter().all(Result::is_ok));
+    assert_eq!(responses.len(), 2);
+
+    mock_speech.assert();
+}
+
+#[tokio::test]
+async fn speech_with_length_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let audio: Vec<u8> = (0..1_000).map(|index| (index % 256) as u8).collect();
+
+    let mock_speech = server
+        .mock("POST", "/speech")
+        .with_status(200)
+        .with_body_from_file("tests/files/speech/transcription.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Content-Length", "1000")
+        .match_header("Transfer-Encoding", Matcher::Missing)
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(audio.clone())
+        .create();
+
+    // a streamed body, which would otherwise be chunked
+    let chunks: Vec<Result<Vec<u8>, std::io::Error>> =
+        audio.chunks(300).map(|chunk| Ok(chunk.to_vec())).collect();
+
+    let responses: Vec<_> = client
+        .speech_with_length(
+            reqwest::Body::wrap_stream(futures::stream::iter(chunks)),
+            AudioType::MP3,
+            MessageOptions::default(),
+            audio.len() as u64,
+        )
+        .await
+        .unwrap()
+        .collect()
+        .await;
+
+    assert!(responses.iter().all(Result::is_ok));
+
+    mock_speech.assert();
+}
@@ -0,0 +1,137 @@
+use mockito::Matcher;
+use serde_json::json;
+use wit_ai_rs::{
+    client::WitClient,
+    common_types::{AudioType, RawAudioConfig, RawAudioConfigBuilder},
+    errors::Error,
+    synthesize::SynthesizeRequestBuilder,
+};
+
+#[tokio::test]
+async fn synthesize_output_format_mock() {
+    let audio: Vec<u8> = vec![0x52, 0x49, 0x46, 0x46, 0x00, 0xff, 0x10, 0x80];
+
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let wit_client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("POST", "/synthesize")
+        .with_status(200)
+        .with_header("Content-Type", "audio/wav")
+        .with_body(&audio)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "audio/wav")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(Matcher::Json(json!({
+            "q": "hello world",
+            "voice": "Rebecca",
+            "speed": 120
+        })))
+        .create();
+
+    let request = SynthesizeRequestBuilder::new("hello world", "Rebecca")
+        .speed(120)
+        .output_format(AudioType::WAV)
+        .unwrap()
+        .build();
+
+    let response = wit_client.synthesize(request).await.unwrap();
+
+    mock.assert();
+
+    assert_eq!(response.audio, audio);
+    assert_eq!(response.audio_type, AudioType::WAV);
+}
+
+#[tokio::test]
+async fn synthesize_raw_output_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let wit_client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("POST", "/synthesize")
+        .with_status(200)
+        .with_header("Content-Type", "audio/pcm16")
+        .with_body([1, 2, 3, 4])
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "audio/pcm16")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let request = SynthesizeRequestBuilder::new("hello world", "Rebecca")
+        .output_format(AudioType::Raw(RawAudioConfig::default()))
+        .unwrap()
+        .build();
+
+    let response = wit_client.synthesize(request).await.unwrap();
+
+    mock.assert();
+
+    assert_eq!(response.audio, vec![1, 2, 3, 4]);
+    assert_eq!(
+        response.audio_type,
+        AudioType::Raw(RawAudioConfig::default())
+    );
+}
+
+#[tokio::test]
+async fn synthesize_negotiated_type_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let wit_client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    // the audio type comes from the response, even when it differs from the request
+    let mock = server
+        .mock("POST", "/synthesize")
+        .with_status(200)
+        .with_header("Content-Type", "audio/wav")
+        .with_body([1, 2, 3, 4])
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_header("Accept", "audio/mpeg")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .create();
+
+    let request = SynthesizeRequestBuilder::new("hello world", "Rebecca").build();
+
+    let response = wit_client.synthesize(request).await.unwrap();
+
+    mock.assert();
+
+    assert_eq!(response.audio_type, AudioType::WAV);
+}
+
+#[test]
+fn synthesize_unsupported_output_format() {
+    let config = RawAudioConfigBuilder::new()
+        .sample_rate(8_000)
+        .build()
+        .unwrap();
+
+    let result = SynthesizeRequestBuilder::new("hello world", "Rebecca")
+        .output_format(AudioType::Raw(config));
+
+    assert!(matches!(result, Err(Error::InvalidArgument(_))));
+}
@@ -1,6 +1,11 @@
+// the live tests pass the token as an owned `String`, to show that callers written before
+// `WitClient::new` accepted `impl Into<String>` still compile
+#![allow(clippy::useless_conversion)]
+
 use mockito::Matcher;
 use wit_ai_rs::{
     client::WitClient,
+    errors::Error,
     traits::{NewTrait, TraitResponse, TraitValue},
     DeleteResponse, TraitBasic,
 };
@@ -59,8 +64,9 @@ async fn get_all_traits_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("GET", "/traits")
@@ -101,8 +107,9 @@ async fn create_trait_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("POST", "/traits")
@@ -148,8 +155,9 @@ async fn get_trait_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("GET", "/traits/politeness")
@@ -192,8 +200,9 @@ async fn delete_trait_mock() {
     let mut server = mockito::Server::new_async().await;
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock = server
         .mock("DELETE", "/traits/politeness")
@@ -219,3 +228,225 @@ async fn delete_trait_mock() {
 
     mock.assert();
 }
+
+#[tokio::test]
+async fn get_trait_not_found_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("GET", "/traits/missing")
+        .with_status(404)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Trait not found", "code": "not-found"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let error = client.get_trait("missing").await.unwrap_err();
+
+    assert!(matches!(error, Error::TraitNotFound(name, _) if name == "missing"));
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn update_trait_values_mock() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let version_query = Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned());
+
+    // fetched once before the update and once after it
+    let mock_get = server
+        .mock("GET", "/traits/politeness")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/traits/get_one.json")
+        .match_query(version_query.clone())
+        .expect(2)
+        .create();
+
+    // only the missing value is added...
+    let mock_add = server
+        .mock("POST", "/traits/politeness/values")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/traits/get_one.json")
+        .match_query(version_query.clone())
+        .match_body(Matcher::Json(serde_json::json!({"value": "neutral"})))
+        .expect(1)
+        .create();
+
+    // ...and only the value that is no longer listed is deleted
+    let mock_delete = server
+        .mock("DELETE", "/traits/politeness/values/rude")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"deleted": "rude"}"#)
+        .match_query(version_query)
+        .expect(1)
+        .create();
+
+    let response = client
+        .update_trait_values(
+            "politeness",
+            vec![String::from("polite"), String::from("neutral")],
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.name, "politeness");
+
+    mock_get.assert();
+    mock_add.assert();
+    mock_delete.assert();
+}
+
+#[tokio::test]
+async fn update_trait_values_repeated_mock() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let version_query = Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned());
+
+    let mock_get = server
+        .mock("GET", "/traits/politeness")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/traits/get_one.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(version_query.clone())
+        .expect(2)
+        .create();
+
+    // a value listed twice is still only added once
+    let mock_add = server
+        .mock("POST", "/traits/politeness/values")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/traits/get_one.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(version_query.clone())
+        .match_body(Matcher::Json(serde_json::json!({"value": "neutral"})))
+        .expect(1)
+        .create();
+
+    let _mock_delete = server
+        .mock("DELETE", "/traits/politeness/values/rude")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"deleted": "rude"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(version_query)
+        .create();
+
+    client
+        .update_trait_values(
+            "politeness",
+            vec![
+                String::from("polite"),
+                String::from("neutral"),
+                String::from("neutral"),
+            ],
+        )
+        .await
+        .unwrap();
+
+    mock_get.assert();
+    mock_add.assert();
+}
+
+#[test]
+fn new_trait_to_json() {
+    let new_trait = NewTrait::new(
+        "politeness",
+        vec![String::from("polite"), String::from("rude")],
+    );
+
+    assert_eq!(
+        new_trait.to_json(),
+        serde_json::json!({
+            "name": "politeness",
+            "values": ["polite", "rude"]
+        })
+    );
+}
+
+#[tokio::test]
+async fn delete_trait_value_encoded_mock() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    // the `/` and space are part of the value, rather than separating path segments
+    let mock = server
+        .mock("DELETE", "/traits/politeness/values/very%20polite%2Frude")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"deleted": "very polite/rude"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let response = client
+        .delete_trait_value("politeness", "very polite/rude")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response,
+        DeleteResponse {
+            deleted: String::from("very polite/rude"),
+        }
+    );
+
+    mock.assert();
+}
+
+#[tokio::test]
+async fn delete_trait_value_not_found_mock() {
+    let mut server = mockito::Server::new_async().await;
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock = server
+        .mock("DELETE", "/traits/missing/values/polite")
+        .with_status(404)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"error": "Trait not found", "code": "not-found"}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::Any)
+        .create();
+
+    let result = client.delete_trait_value("missing", "polite").await;
+
+    assert!(matches!(result, Err(Error::TraitNotFound(name, _)) if name == "missing"));
+
+    mock.assert();
+}
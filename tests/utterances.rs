@@ -1,10 +1,19 @@
+// the live tests pass the token as an owned `String`, to show that callers written before
+// `WitClient::new` accepted `impl Into<String>` still compile
+#![allow(clippy::useless_conversion)]
+
+use futures::{StreamExt, TryStreamExt};
 use mockito::Matcher;
 use wit_ai_rs::{
     client::WitClient,
+    errors::Error,
+    message::MessageResponse,
     utterances::{
-        CreateUtteranceResponse, DeleteUtteranceResponse, GetUtterancesRequestBuilder,
-        NewUtterance, NewUtteranceEntity, UtteranceResponse, UtteranceResponseEntity,
-        UtteranceResponseTrait,
+        CreateUtteranceResponse, DeleteUtteranceResponse, FailedUtterance,
+        GetUtterancesRequestBuilder, NewUtterance, NewUtteranceEntity, NewUtteranceTrait,
+        UtteranceBatcher, UtterancePage, UtteranceReference, UtteranceResponse,
+        UtteranceResponseEntity, UtteranceResponseTrait, ValidationIssue,
+        VerifiedCreateUtterancesResponse, MAX_UTTERANCES_LIMIT,
     },
     IntentBasic,
 };
@@ -66,8 +75,9 @@ async fn get_utterances_mock() {
 
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock_utterances = server
         .mock("GET", "/utterances")
@@ -84,10 +94,10 @@ async fn get_utterances_mock() {
 
     let expected_response = vec![UtteranceResponse {
         text: String::from("I want to fly SFO"),
-        intent: IntentBasic {
+        intent: Some(IntentBasic {
             id: String::from("928398303890"),
             name: String::from("flight_request"),
-        },
+        }),
         entities: vec![UtteranceResponseEntity {
             id: String::from("120890890090903"),
             name: String::from("wit$location"),
@@ -104,7 +114,7 @@ async fn get_utterances_mock() {
         }],
     }];
 
-    let request = GetUtterancesRequestBuilder::new(100).unwrap().build();
+    let request = GetUtterancesRequestBuilder::default().build();
 
     let response = client.get_utterances(request).await.unwrap();
 
@@ -113,14 +123,194 @@ async fn get_utterances_mock() {
     mock_utterances.assert();
 }
 
+#[tokio::test]
+async fn get_utterances_out_of_scope_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_utterances = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/get_out_of_scope.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), 100.to_string()),
+        ]))
+        .create();
+
+    let expected_response = vec![UtteranceResponse {
+        text: String::from("what is the meaning of life"),
+        intent: None,
+        entities: vec![],
+        traits: vec![],
+    }];
+
+    let request = GetUtterancesRequestBuilder::default().build();
+
+    let response = client.get_utterances(request).await.unwrap();
+
+    assert_eq!(response, expected_response);
+
+    mock_utterances.assert();
+}
+
+#[tokio::test]
+async fn get_utterances_max_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_utterances = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body("[]")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), MAX_UTTERANCES_LIMIT.to_string()),
+        ]))
+        .create();
+
+    let request = GetUtterancesRequestBuilder::max().offset(0).build();
+
+    let response = client.get_utterances(request).await.unwrap();
+
+    assert_eq!(response, vec![]);
+
+    mock_utterances.assert();
+}
+
+#[tokio::test]
+async fn get_utterances_stream_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let count = 5000;
+
+    // the text includes characters that are significant in JSON, to check that they are not
+    // mistaken for the boundaries of elements
+    let utterances: Vec<String> = (0..count)
+        .map(|index| {
+            format!(
+                r#"{{"text": "utterance {index}, with [brackets], {{braces}} and \"quotes\"", "intent": null, "entities": [], "traits": []}}"#
+            )
+        })
+        .collect();
+
+    let body = format!("[{}]", utterances.join(",\n"));
+
+    let mock_utterances = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_chunked_body(move |writer| {
+            // send the body in small pieces, so that elements are split across chunks
+            for piece in body.as_bytes().chunks(1000) {
+                writer.write_all(piece)?;
+                writer.flush()?;
+            }
+            Ok(())
+        })
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), MAX_UTTERANCES_LIMIT.to_string()),
+        ]))
+        .create();
+
+    let request = GetUtterancesRequestBuilder::max().build();
+
+    let mut received = 0;
+
+    let mut stream = Box::pin(client.get_utterances_stream(request));
+
+    while let Some(utterance) = stream.next().await {
+        let utterance = utterance.unwrap();
+
+        assert_eq!(
+            utterance.text,
+            format!(r#"utterance {received}, with [brackets], {{braces}} and "quotes""#)
+        );
+
+        received += 1;
+    }
+
+    assert_eq!(received, count);
+
+    mock_utterances.assert();
+}
+
+#[tokio::test]
+async fn export_utterances_resume_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    // the first page was already exported before the interruption
+    let mock_first_page = server
+        .mock("GET", "/utterances")
+        .match_query(Matcher::UrlEncoded(String::from("offset"), 0.to_string()))
+        .expect(0)
+        .create();
+
+    let mock_second_page = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), MAX_UTTERANCES_LIMIT.to_string()),
+            Matcher::UrlEncoded(String::from("offset"), MAX_UTTERANCES_LIMIT.to_string()),
+        ]))
+        .create();
+
+    let pages: Vec<UtterancePage> = client
+        .export_utterances_from(MAX_UTTERANCES_LIMIT)
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].utterances.len(), 1);
+    assert_eq!(pages[0].utterances[0].text, "I want to fly SFO");
+    assert_eq!(pages[0].next_offset, MAX_UTTERANCES_LIMIT + 1);
+
+    mock_first_page.assert();
+    mock_second_page.assert();
+}
+
 #[tokio::test]
 async fn create_utterances_mock() {
     let mut server = mockito::Server::new_async().await;
 
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock_utterances = server
         .mock("POST", "/utterances")
@@ -157,14 +347,138 @@ async fn create_utterances_mock() {
     mock_utterances.assert();
 }
 
+#[tokio::test]
+async fn create_utterances_verified_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_create = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/create.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .create();
+
+    let mock_get = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/get_all.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), MAX_UTTERANCES_LIMIT.to_string()),
+            Matcher::UrlEncoded(String::from("intents"), String::from("flight_request")),
+        ]))
+        .create();
+
+    // only the first of these exists in the mocked utterances
+    let new_utterances = vec![
+        NewUtterance::new(
+            String::from("I want to fly SFO"),
+            vec![],
+            vec![],
+            Some(String::from("flight_request")),
+        ),
+        NewUtterance::new(
+            String::from("I want to fly JFK"),
+            vec![],
+            vec![],
+            Some(String::from("flight_request")),
+        ),
+    ];
+
+    let response = client
+        .create_utterances_verified(new_utterances)
+        .await
+        .unwrap();
+
+    let expected_response = VerifiedCreateUtterancesResponse {
+        n: 1,
+        failed: vec![FailedUtterance {
+            text: String::from("I want to fly JFK"),
+            intent: Some(String::from("flight_request")),
+        }],
+    };
+
+    assert_eq!(response, expected_response);
+
+    mock_create.assert();
+    mock_get.assert();
+}
+
+#[tokio::test]
+async fn submit_correction_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_utterances = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/create.json")
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            client.get_version().to_owned(),
+        ))
+        .match_body(Matcher::Regex(String::from(
+            r#"^\[\{"text":"how many people between Tuesday and Friday".*"intent":"report"\}\]$"#,
+        )))
+        .create();
+
+    let contents = std::fs::read_to_string("tests/files/message.json").unwrap();
+
+    let response: MessageResponse = serde_json::from_str(&contents).unwrap();
+
+    let corrected = NewUtterance::new(
+        response.text.clone(),
+        vec![],
+        vec![],
+        Some(String::from("report")),
+    );
+
+    let result = client
+        .submit_correction(&response, corrected)
+        .await
+        .unwrap();
+
+    assert_eq!(result, CreateUtteranceResponse { sent: true, n: 1 });
+
+    let mismatched = NewUtterance::new(String::from("something else"), vec![], vec![], None);
+
+    assert!(matches!(
+        client.submit_correction(&response, mismatched).await,
+        Err(Error::InvalidArgument(_))
+    ));
+
+    mock_utterances.assert();
+}
+
 #[tokio::test]
 async fn delete_utterances_mock() {
     let mut server = mockito::Server::new_async().await;
 
     let url = server.url();
 
-    let client =
-        WitClient::new(String::from("TEST_TOKEN"), String::from("20231231")).set_api_host(url);
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
 
     let mock_utterances = server
         .mock("DELETE", "/utterances")
@@ -189,3 +503,257 @@ async fn delete_utterances_mock() {
 
     mock_utterances.assert();
 }
+
+#[tokio::test]
+async fn utterance_batcher_close_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_full_batch = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"sent": true, "n": 2}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(Matcher::Regex(String::from(
+            r#"^\[\{"text":"utterance 0".*\{"text":"utterance 1".*\]$"#,
+        )))
+        .create();
+
+    let mock_remainder = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"sent": true, "n": 1}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(Matcher::Regex(String::from(
+            r#"^\[\{"text":"utterance 2"[^\{]*\}\]$"#,
+        )))
+        .create();
+
+    let mut batcher = UtteranceBatcher::new(client, 2).unwrap();
+
+    let mut responses = Vec::new();
+
+    for index in 0..3 {
+        let utterance = NewUtterance::new(format!("utterance {index}"), vec![], vec![], None);
+
+        responses.push(batcher.add(utterance).await.unwrap());
+    }
+
+    assert_eq!(
+        responses,
+        vec![
+            None,
+            Some(CreateUtteranceResponse { sent: true, n: 2 }),
+            None
+        ]
+    );
+    assert_eq!(batcher.len(), 1);
+
+    let response = batcher.close().await.unwrap();
+
+    assert_eq!(response, Some(CreateUtteranceResponse { sent: true, n: 1 }));
+
+    mock_full_batch.assert();
+    mock_remainder.assert();
+}
+
+#[tokio::test]
+async fn validate_utterances_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let list_mocks: Vec<_> = ["intents", "entities", "traits"]
+        .iter()
+        .map(|endpoint| {
+            server
+                .mock("GET", format!("/{endpoint}").as_str())
+                .with_status(200)
+                .with_header("Content-Type", "application/json")
+                .with_body_from_file(format!("tests/files/{endpoint}/get_all.json"))
+                .match_query(Matcher::UrlEncoded(
+                    String::from("v"),
+                    client.get_version().to_owned(),
+                ))
+                .create()
+        })
+        .collect();
+
+    // references the `flight_request` intent, which is not in the app
+    let utterances_mock = server
+        .mock("GET", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body_from_file("tests/files/utterances/get_all.json")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(String::from("v"), client.get_version().to_owned()),
+            Matcher::UrlEncoded(String::from("limit"), MAX_UTTERANCES_LIMIT.to_string()),
+            Matcher::UrlEncoded(String::from("offset"), 0.to_string()),
+        ]))
+        .create();
+
+    let issues = client.validate_utterances().await.unwrap();
+
+    for mock in list_mocks {
+        mock.assert();
+    }
+    utterances_mock.assert();
+
+    assert_eq!(
+        issues,
+        vec![ValidationIssue {
+            text: String::from("I want to fly SFO"),
+            missing: UtteranceReference::Intent(String::from("flight_request")),
+        }]
+    );
+}
+
+#[tokio::test]
+async fn import_utterances_ndjson_mock() {
+    let mut server = mockito::Server::new_async().await;
+
+    let url = server.url();
+
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"))
+        .set_api_host(url)
+        .unwrap();
+
+    let mock_full_batch = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"sent": true, "n": 2}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(Matcher::Json(serde_json::json!([
+            {
+                "text": "I want to fly to sfo",
+                "intent": "flight_request",
+                "entities": [
+                    {"entity": "wit$location:to", "start": 17, "end": 20, "body": "sfo", "entities": []}
+                ],
+                "traits": []
+            },
+            {"text": "hello", "intent": null, "entities": [], "traits": []}
+        ])))
+        .create();
+
+    let mock_remainder = server
+        .mock("POST", "/utterances")
+        .with_status(200)
+        .with_header("Content-Type", "application/json")
+        .with_body(r#"{"sent": true, "n": 1}"#)
+        .match_header("Authorization", "Bearer TEST_TOKEN")
+        .match_query(Matcher::UrlEncoded(
+            String::from("v"),
+            String::from("20231231"),
+        ))
+        .match_body(Matcher::Json(serde_json::json!([
+            {
+                "text": "that was great",
+                "intent": null,
+                "entities": [],
+                "traits": [{"trait": "sentiment", "value": "positive"}]
+            }
+        ])))
+        .create();
+
+    let ndjson = concat!(
+        r#"{"text": "I want to fly to sfo", "intent": "flight_request", "entities": [{"entity": "wit$location:to", "start": 17, "end": 20, "body": "sfo"}]}"#,
+        "\n",
+        r#"{"text": "hello"}"#,
+        "\n\n",
+        r#"{"text": "that was great", "traits": [{"trait": "sentiment", "value": "positive"}]}"#,
+        "\n",
+    );
+
+    let response = client
+        .import_utterances_ndjson(ndjson.as_bytes(), 2)
+        .await
+        .unwrap();
+
+    assert_eq!(response, CreateUtteranceResponse { sent: true, n: 3 });
+
+    mock_full_batch.assert();
+    mock_remainder.assert();
+}
+
+#[tokio::test]
+async fn import_utterances_ndjson_malformed_line() {
+    let client = WitClient::new(String::from("TEST_TOKEN"), String::from("20231231"));
+
+    let ndjson = "{\"text\": \"hello\"}\n\n{\"intent\": \"greeting\"}\n";
+
+    // the second utterance is missing its text, and is found before anything is sent
+    let error = client
+        .import_utterances_ndjson(ndjson.as_bytes(), 10)
+        .await
+        .unwrap_err();
+
+    match error {
+        Error::JSONParseError(details) => assert!(details.starts_with("line 3:"), "{details}"),
+        other => panic!("expected a JSONParseError, got {other:?}"),
+    }
+}
+
+#[test]
+fn new_utterance_to_json() {
+    let utterance = NewUtterance::new(
+        "I want to fly to sfo",
+        vec![NewUtteranceEntity::new(
+            "wit$location:to",
+            17,
+            20,
+            "sfo",
+            vec![],
+        )],
+        vec![NewUtteranceTrait::new("wit$sentiment", "neutral")],
+        Some(String::from("flight_request")),
+    );
+
+    // matches the example in wit's documentation for `POST /utterances`
+    assert_eq!(
+        utterance.to_json(),
+        serde_json::json!({
+            "text": "I want to fly to sfo",
+            "intent": "flight_request",
+            "entities": [
+                {
+                    "entity": "wit$location:to",
+                    "start": 17,
+                    "end": 20,
+                    "body": "sfo",
+                    "entities": []
+                }
+            ],
+            "traits": [
+                {
+                    "trait": "wit$sentiment",
+                    "value": "neutral"
+                }
+            ]
+        })
+    );
+}